@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::ron_asset::RonAssetLoader;
+use crate::rules::AttackPattern;
+
+pub const ATTACK_PATTERNS_PATH: &str = "attack_patterns.ron";
+
+// Every attack in the game, designers tune them in assets/attack_patterns.ron.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct AttackPatterns {
+    pub patterns: HashMap<String, AttackPattern>,
+    // Pattern ids for the Q, W, E and R attacks.
+    pub player: Vec<String>,
+}
+
+impl AttackPatterns {
+    pub fn get(&self, id: &str) -> Option<&AttackPattern> {
+        self.patterns.get(id)
+    }
+
+    pub fn player_slot(&self, slot: usize) -> Option<&AttackPattern> {
+        self.player.get(slot).and_then(|id| self.get(id))
+    }
+}
+
+#[derive(Resource)]
+pub struct AttackPatternsHandle(pub Handle<AttackPatterns>);
+
+pub struct AttackPatternsPlugin;
+
+impl Plugin for AttackPatternsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AttackPatterns>()
+            .init_asset_loader::<RonAssetLoader<AttackPatterns>>()
+            .add_systems(Startup, load_attack_patterns)
+            .add_systems(Update, log_attack_pattern_reloads);
+    }
+}
+
+fn load_attack_patterns(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(AttackPatternsHandle(
+        asset_server.load(ATTACK_PATTERNS_PATH),
+    ));
+}
+
+// The systems read the asset on every attack, so edits apply on the next one.
+fn log_attack_pattern_reloads(mut events: MessageReader<AssetEvent<AttackPatterns>>) {
+    for event in events.read() {
+        if let AssetEvent::Modified { .. } = event {
+            println!("Attack patterns reloaded.");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::components::*;
 use crate::constants::*;
 use bevy::{color::palettes::basic::*, input_focus::InputFocus, prelude::*};
 
@@ -10,21 +11,14 @@ pub fn button_system(
             &mut BackgroundColor,
             &mut BorderColor,
             &mut Button,
-            &Children,
         ),
         Changed<Interaction>,
     >,
-    mut text_query: Query<&mut Text>,
 ) {
-    for (entity, interaction, mut color, mut border_color, mut button, children) in
-        &mut interaction_query
-    {
-        let mut text = text_query.get_mut(children[0]).unwrap();
-
+    for (entity, interaction, mut color, mut border_color, mut button) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
                 input_focus.set(entity);
-                **text = "Press".to_string();
                 *color = PRESSED_BUTTON.into();
                 *border_color = BorderColor::all(RED);
 
@@ -33,14 +27,12 @@ pub fn button_system(
             }
             Interaction::Hovered => {
                 input_focus.set(entity);
-                **text = "Hover".to_string();
                 *color = HOVERED_BUTTON.into();
                 *border_color = BorderColor::all(Color::WHITE);
                 button.set_changed();
             }
             Interaction::None => {
                 input_focus.clear();
-                **text = "Button".to_string();
                 *color = NORMAL_BUTTON.into();
                 *border_color = BorderColor::all(Color::BLACK);
             }
@@ -48,40 +40,33 @@ pub fn button_system(
     }
 }
 
-pub fn button(asset_server: &AssetServer) -> impl Bundle {
+// Labeled button, what it does when pressed is decided by the action.
+pub fn button(asset_server: &AssetServer, label: &str, action: ButtonAction) -> impl Bundle {
     (
+        Button,
+        action,
         Node {
-            width: percent(100),
-            height: percent(100),
-            align_items: AlignItems::Center,
+            width: px(220),
+            height: px(65),
+            border: UiRect::all(px(5)),
+            // horizontally center child text
             justify_content: JustifyContent::Center,
+            // vertically center child text
+            align_items: AlignItems::Center,
+            border_radius: BorderRadius::MAX,
             ..default()
         },
+        BorderColor::all(Color::BLACK),
+        BackgroundColor(NORMAL_BUTTON),
         children![(
-            Button,
-            Node {
-                width: px(150),
-                height: px(65),
-                border: UiRect::all(px(5)),
-                // horizontally center child text
-                justify_content: JustifyContent::Center,
-                // vertically center child text
-                align_items: AlignItems::Center,
-                border_radius: BorderRadius::MAX,
+            Text::new(label),
+            TextFont {
+                font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+                font_size: 33.0,
                 ..default()
             },
-            BorderColor::all(Color::WHITE),
-            BackgroundColor(Color::BLACK),
-            children![(
-                Text::new("Button"),
-                TextFont {
-                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                    font_size: 33.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                TextShadow::default(),
-            )]
+            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            TextShadow::default(),
         )],
     )
 }
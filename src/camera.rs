@@ -1,5 +1,7 @@
 use bevy::{math::Vec3, prelude::*};
 
+use crate::post_processing;
+
 // Code from: https://github.com/StarArawn/bevy_ecs_tilemap/blob/main/examples/helpers/camera.rs
 // A simple camera system for moving and zooming the camera.
 pub fn update_camera(mut query: Query<(&mut Transform, &mut Projection), With<Camera>>) {
@@ -17,3 +19,15 @@ pub fn update_camera(mut query: Query<(&mut Transform, &mut Projection), With<Ca
         transform.translation.z = z;
     }
 }
+
+pub fn setup_camera(mut commands: Commands) {
+    // Spawn Camera, it lives for the whole application so the menus can use it too.
+    commands.spawn((
+        Camera2d, // Add the setting to the camera.
+        // This component is also used to determine on which camera to run the post processing effect.
+        post_processing::PostProcessSettings {
+            intensity: 25.2,
+            ..default()
+        },
+    ));
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::ron_asset::RonAssetLoader;
+
+pub const CHARACTERS_PATH: &str = "characters.ron";
+
+// Stats of a playable character, designers tune them in assets/characters.ron.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Character {
+    // Tiles the character can move every turn.
+    #[serde(default = "default_action_points")]
+    pub action_points: u32,
+}
+
+fn default_action_points() -> u32 {
+    1
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct Characters {
+    pub characters: HashMap<String, Character>,
+    // Id of the character the player plays.
+    pub player: String,
+}
+
+impl Characters {
+    pub fn get(&self, id: &str) -> Option<&Character> {
+        self.characters.get(id)
+    }
+
+    pub fn player(&self) -> Option<&Character> {
+        self.get(&self.player)
+    }
+}
+
+#[derive(Resource)]
+pub struct CharactersHandle(pub Handle<Characters>);
+
+pub struct CharactersPlugin;
+
+impl Plugin for CharactersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Characters>()
+            .init_asset_loader::<RonAssetLoader<Characters>>()
+            .add_systems(Startup, load_characters)
+            .add_systems(Update, log_character_reloads);
+    }
+}
+
+fn load_characters(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CharactersHandle(asset_server.load(CHARACTERS_PATH)));
+}
+
+// The budget is read when a move starts, so edits apply on the next turn.
+fn log_character_reloads(mut events: MessageReader<AssetEvent<Characters>>) {
+    for event in events.read() {
+        if let AssetEvent::Modified { .. } = event {
+            println!("Characters reloaded.");
+        }
+    }
+}
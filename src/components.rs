@@ -1,5 +1,9 @@
 use bevy::prelude::*;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{Abilities, EnemyKind};
 
 #[derive(Component)]
 pub struct PlayZoneTilemap;
@@ -15,8 +19,7 @@ pub struct RoundColorState {
 }
 
 impl RoundColorState {
-    pub fn asign_random_color(&mut self) {
-        let mut rng = rand::rng();
+    pub fn asign_random_color(&mut self, rng: &mut impl Rng) {
         self.index = rng.random_range(0..3); // 0 - red, 1 - green, 2 - blue
     }
 }
@@ -27,6 +30,83 @@ impl Default for RoundColorState {
     }
 }
 
+// Every random decision goes through this so a run can be reproduced from its seed.
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng {
+    seed: u64,
+    // Seeds given on the command line or in the config are kept for every run.
+    fixed_seed: bool,
+    // Seed for the next run only, replays play with the seed they were recorded with.
+    next_seed: Option<u64>,
+    #[deref]
+    rng: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        GameRng {
+            seed,
+            fixed_seed: true,
+            next_seed: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn from_entropy() -> Self {
+        let seed = rand::rng().random();
+        GameRng {
+            seed,
+            fixed_seed: false,
+            next_seed: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    // Seed from `--seed <number>` or the COLOR_WIZARD_SEED variable, random otherwise.
+    pub fn from_env() -> Self {
+        let seed = std::env::args()
+            .skip_while(|arg| arg != "--seed")
+            .nth(1)
+            .or_else(|| std::env::var("COLOR_WIZARD_SEED").ok())
+            .and_then(|seed| seed.trim().parse().ok());
+
+        match seed {
+            Some(seed) => GameRng::from_seed(seed),
+            None => GameRng::from_entropy(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Called when a run starts, a fixed seed replays the same run, otherwise a new seed is rolled.
+    pub fn start_run(&mut self) {
+        if let Some(seed) = self.next_seed.take() {
+            self.seed = seed;
+        } else if !self.fixed_seed {
+            self.seed = rand::rng().random();
+        }
+        self.rng = ChaCha8Rng::seed_from_u64(self.seed);
+    }
+
+    pub fn queue_seed(&mut self, seed: u64) {
+        self.next_seed = Some(seed);
+    }
+
+    // How far the run is into the random stream, a saved run continues from here.
+    pub fn word_pos(&self) -> u64 {
+        // A run never draws anywhere near u64::MAX words.
+        self.rng.get_word_pos() as u64
+    }
+
+    pub fn resume(&mut self, seed: u64, word_pos: u64) {
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self.rng.set_word_pos(word_pos as u128);
+    }
+}
+
 #[derive(Component)]
 pub struct SpectrumElement;
 
@@ -45,30 +125,31 @@ pub struct Player;
 #[derive(Component)]
 pub struct Enemy;
 
+// Which archetype an enemy is, enemies without it play as a grunt.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref)]
+pub struct Kind(pub EnemyKind);
+
+// Hits left before the unit dies, units without it die on the first one.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Deref, DerefMut)]
+pub struct Health(pub u32);
+
 #[derive(Component)]
 pub struct Troop;
 
+#[derive(States, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ApplicationState {
+    #[default]
     MainMenu,
     Gameplay,
     EndMenu,
+    Scoreboard,
 }
 
-#[derive(Component)]
-pub struct GlobalApplicationState {
-    pub application_state: ApplicationState,
-}
-
-impl Default for GlobalApplicationState {
-    fn default() -> Self {
-        GlobalApplicationState {
-            application_state: ApplicationState::Gameplay,
-        }
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
+// The turn based mechanics of the game, only exists while in gameplay.
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[source(ApplicationState = ApplicationState::Gameplay)]
 pub enum TurnState {
+    #[default]
     ColorPick,
     PlayerChange,
     EnemySpawn,
@@ -78,21 +159,89 @@ pub enum TurnState {
     AttackEnemy,
 }
 
-#[derive(Component)]
-pub struct GlobalTurnState {
-    pub turn_state: TurnState,
+// Enemy attacks are split in a windup and a cooldown so the player can see what happens.
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[source(TurnState = TurnState::AttackEnemy)]
+pub enum EnemyAttackPhase {
+    #[default]
+    Windup,
+    Cooldown,
 }
 
-impl Default for GlobalTurnState {
+// Escape pauses the game, time stands still until it's resumed.
+#[derive(SubStates, Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[source(ApplicationState = ApplicationState::Gameplay)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+    Settings,
+    // Asks before leaving the run, a misclick shouldn't end it.
+    ConfirmQuit(QuitTarget),
+}
+
+// Where a confirmed quit from the pause menu goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuitTarget {
+    Desktop,
+    Menu,
+}
+
+// Shared delay for the turn phases, restarted when a phase is entered.
+#[derive(Resource, Deref, DerefMut)]
+pub struct TurnTimer(pub Timer);
+
+impl Default for TurnTimer {
     fn default() -> Self {
-        GlobalTurnState {
-            turn_state: TurnState::ColorPick,
-        }
+        TurnTimer(Timer::from_seconds(0.0, TimerMode::Once))
     }
 }
 
-impl GlobalTurnState {
-    pub fn modify_state(&mut self, new_state: TurnState) {
-        self.turn_state = new_state;
-    }
+// Damage is ignored until the turn count passes this turn.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Invulnerable {
+    pub until_turn: u32,
+}
+
+// Killed unit playing its death animation, it's despawned when the timer finishes.
+#[derive(Component, Deref, DerefMut)]
+pub struct Dying(pub Timer);
+
+// Turns played in the current run.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct TurnCount(pub u32);
+
+// How the last run ended, shown on the end screen.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunOutcome {
+    #[default]
+    Defeat,
+    Victory,
+}
+
+// Moves the player has left this turn, refilled from the character before the move starts.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+pub struct ActionPoints(pub u32);
+
+// Attack cooldowns and charges of the current run.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct PlayerAbilities(pub Abilities);
+
+// What a menu button does when pressed.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonAction {
+    Continue,
+    Play,
+    Restart,
+    Scoreboard,
+    Back,
+    Quit,
+    // Pause menu
+    Resume,
+    Pause,
+    Settings,
+    Fullscreen,
+    SaveAndQuit,
+    QuitToMenu,
+    ConfirmQuit,
 }
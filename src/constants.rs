@@ -9,6 +9,32 @@ pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 pub const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 pub const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
 
+// Hover preview of the Q, W, E and R attack footprints.
+pub const ATTACK_PREVIEW_COLORS: [Color; 4] = [
+    Color::srgba(1.0, 1.0, 1.0, 0.25),
+    Color::srgba(1.0, 0.9, 0.3, 0.25),
+    Color::srgba(0.3, 0.9, 1.0, 0.25),
+    Color::srgba(1.0, 0.4, 1.0, 0.25),
+];
+
+// Outline on the tiles the enemies are about to hit, its alpha pulses between the two values.
+pub const INTENT_COLOR: Color = Color::srgb(1.0, 0.2, 0.2);
+pub const INTENT_ALPHA: (f32, f32) = (0.15, 0.55);
+pub const INTENT_PULSE_SECONDS: f32 = 0.8;
+
 pub const MOVE_DELAY_SECONDS: f32 = 0.4;
 pub const ENEMY_ATTACK_WINDUP_SECONDS: f32 = 1.3;
-pub const ENEMY_ATTACK_COOLDOWN_SECONDS: f32 = 1.3;
\ No newline at end of file
+pub const ENEMY_ATTACK_COOLDOWN_SECONDS: f32 = 1.3;
+
+// Finished turns the player can still undo, the turn being played can always be undone.
+pub const UNDO_TURNS: usize = 1;
+// Undos a run gets, every press that rewinds something uses one.
+pub const UNDOS_PER_RUN: u32 = 3;
+
+pub const PLAYER_LIVES: u32 = 3;
+// After a hit the player can't be hurt again until this many more turns are over.
+pub const PLAYER_INVULNERABLE_TURNS: u32 = 1;
+pub const INVULNERABLE_BLINK_SECONDS: f32 = 0.15;
+pub const DEATH_ANIMATION_SECONDS: f32 = 0.6;
+// How far the left stick has to be pushed before it counts as a move.
+pub const STICK_DEADZONE: f32 = 0.5;
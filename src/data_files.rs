@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+// Folder of the game in the data and config directories.
+const GAME_DIR: &str = "color-wizard";
+
+// Default path of a file the game keeps between runs, like the save, high scores and replays.
+// The path resources default to it, the binary and the tests can insert their own before
+// the plugins, init_resource leaves those alone.
+pub fn data_file(name: &str) -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join(GAME_DIR))
+        .unwrap_or_default()
+        .join(name)
+}
+
+// Same for settings, they go in the config directory.
+pub fn config_file(name: &str) -> PathBuf {
+    dirs::config_dir()
+        .map(|dir| dir.join(GAME_DIR))
+        .unwrap_or_default()
+        .join(name)
+}
+
+// Pretty printed, so the files can be read and edited by hand.
+pub fn save_ron(path: &Path, value: &impl Serialize) -> Result<(), BevyError> {
+    let text = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    write_file(path, &text)
+}
+
+// The folder is made the first time anything is saved.
+pub fn write_file(path: &Path, text: &str) -> Result<(), BevyError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text)?;
+    Ok(())
+}
@@ -1,11 +1,107 @@
+use crate::components::{ApplicationState, GameRng};
+use crate::input::*;
+use crate::playzone::PlayzoneMap;
+use crate::replay::*;
 use bevy::prelude::*;
 use bevy_egui::{EguiContexts, egui};
 
-pub fn render_egui(mut contexts: EguiContexts) -> Result {
+pub fn render_egui(
+    mut contexts: EguiContexts,
+    rng: Res<GameRng>,
+    bindings: Res<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) -> Result {
     egui::Window::new("Color Wizard").show(contexts.ctx_mut()?, |ui| {
-        ui.label("Esc - Exit");
-        ui.label("QWER - Shoot");
-        ui.label("Arrows - Move Around");
+        // Help is built from the bindings so it's always what the keys actually do.
+        for action in InputAction::ALL {
+            let keys = bindings
+                .keys(action)
+                .iter()
+                .map(|key| key_name(*key))
+                .chain(bindings.buttons(action).iter().map(|button| button_name(*button)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            ui.label(format!("{} - {}", keys, action.label()));
+        }
+        ui.separator();
+        ui.label(format!("Seed: {}", rng.seed()));
+
+        ui.collapsing("Controls", |ui| {
+            egui::Grid::new("key_bindings").show(ui, |ui| {
+                for action in InputAction::ALL {
+                    ui.label(action.label());
+                    if rebinding.0 == Some(action) {
+                        ui.label("Press a key or button...");
+                        if ui.button("Cancel").clicked() {
+                            rebinding.0 = None;
+                        }
+                    } else if ui.button("Rebind").clicked() {
+                        rebinding.0 = Some(action);
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    });
+    Ok(())
+}
+
+// Watching the last run, with play, pause, step and speed controls while it plays.
+pub fn render_replay_panel(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    path: Res<ReplayPath>,
+    state: Res<State<ApplicationState>>,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    playzone_map: PlayzoneMap,
+) -> Result {
+    egui::Window::new("Replay").show(contexts.ctx_mut()?, |ui| {
+        let Some(mut playback) = playback else {
+            let can_watch = *state.get() == ApplicationState::MainMenu
+                && path.0.is_file()
+                && playzone_map.is_ready();
+            if ui
+                .add_enabled(can_watch, egui::Button::new("Watch last run"))
+                .clicked()
+                && let Some(replay) = Replay::load(&path.0)
+            {
+                commands.insert_resource(ReplayPlayback::new(replay));
+                next_state.set(ApplicationState::Gameplay);
+            }
+            ui.label(format!("Saved to {}", path.0.display()));
+            return;
+        };
+
+        ui.label(format!(
+            "Seed {}, action {}/{}",
+            playback.replay.seed,
+            playback.next,
+            playback.replay.actions.len()
+        ));
+        if playback.is_finished() {
+            ui.label("Finished");
+        }
+        ui.horizontal(|ui| {
+            let label = if playback.paused { "Play" } else { "Pause" };
+            if ui.button(label).clicked() {
+                playback.paused = !playback.paused;
+            }
+            if ui
+                .add_enabled(playback.paused, egui::Button::new("Step"))
+                .clicked()
+            {
+                playback.step = true;
+            }
+            if ui.button("Stop").clicked() {
+                next_state.set(ApplicationState::MainMenu);
+            }
+        });
+        ui.horizontal(|ui| {
+            for speed in REPLAY_SPEEDS {
+                ui.selectable_value(&mut playback.speed, speed, format!("{}x", speed));
+            }
+        });
     });
     Ok(())
 }
@@ -0,0 +1,62 @@
+// Enemy movement, a breadth first search over the board written without any Bevy types.
+// Every enemy walks toward the closest tile from where its attack reaches the player.
+use std::collections::VecDeque;
+
+use crate::rules::{AttackPattern, Board, Game, MoveDirection, Pos, UnitId};
+use crate::rules::{pattern_tiles, step_in_direction};
+
+const DIRECTIONS: [MoveDirection; 4] = [
+    MoveDirection::Up,
+    MoveDirection::Down,
+    MoveDirection::Left,
+    MoveDirection::Right,
+];
+
+// The tile the enemy should step on this turn, None when it stays where it is.
+pub fn next_step(game: &Game, unit: UnitId, pattern: &AttackPattern) -> Option<Pos> {
+    let enemy = game.unit(unit)?;
+    let player = game.player()?;
+    // Enemies die on the masked color, so they don't walk over it.
+    let walkable = |pos: Pos| game.can_enter(unit, pos);
+    let is_goal = |pos: Pos| pos != player.pos && reaches(pattern, pos, player.pos, &game.board);
+
+    if is_goal(enemy.pos) {
+        return None;
+    }
+
+    // Every tile remembers the first step taken to get there.
+    let board = &game.board;
+    let mut first_steps: Vec<Option<Pos>> = vec![None; (board.width() * board.height()) as usize];
+    let index = |pos: Pos| (pos.y * board.width() + pos.x) as usize;
+    let mut queue = VecDeque::from([enemy.pos]);
+    first_steps[index(enemy.pos)] = Some(enemy.pos);
+
+    while let Some(pos) = queue.pop_front() {
+        for direction in DIRECTIONS {
+            let Some(next) = step_in_direction(board, pos, direction) else {
+                continue;
+            };
+            if first_steps[index(next)].is_some() || !walkable(next) {
+                continue;
+            }
+
+            let first_step = if pos == enemy.pos {
+                next
+            } else {
+                first_steps[index(pos)]?
+            };
+            if is_goal(next) {
+                return Some(first_step);
+            }
+            first_steps[index(next)] = Some(first_step);
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+// Whether an attack from the given tile paints the target.
+pub fn reaches(pattern: &AttackPattern, from: Pos, target: Pos, board: &Board) -> bool {
+    pattern_tiles(pattern, from, board).contains(&target)
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::attack_patterns::AttackPatterns;
+use crate::ron_asset::RonAssetLoader;
+use crate::rules::{EnemyKind, EnemyMovement, EnemyRules};
+
+pub const ENEMY_KINDS_PATH: &str = "enemy_kinds.ron";
+
+// One enemy kind, designers tune them in assets/enemy_kinds.ron.
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnemyArchetype {
+    // Sprite sheet with 16x16 frames in a single row.
+    pub sprite: String,
+    pub frames: u32,
+    pub first: usize,
+    pub last: usize,
+    // Size on screen in pixels, bigger enemies read as tougher.
+    #[serde(default = "default_size")]
+    pub size: f32,
+    // Id of the attack in assets/attack_patterns.ron.
+    pub pattern: String,
+    #[serde(default)]
+    pub movement: EnemyMovement,
+    #[serde(default = "default_health")]
+    pub health: u32,
+    // Relative chance to be picked when an enemy spawns, 0 never spawns.
+    #[serde(default)]
+    pub spawn_weight: u32,
+}
+
+fn default_size() -> f32 {
+    16.0
+}
+
+fn default_health() -> u32 {
+    1
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct EnemyKinds {
+    pub kinds: BTreeMap<EnemyKind, EnemyArchetype>,
+}
+
+impl EnemyKinds {
+    pub fn get(&self, kind: EnemyKind) -> Option<&EnemyArchetype> {
+        self.kinds.get(&kind)
+    }
+
+    // Resolves the pattern ids so the rules get everything they need per kind.
+    pub fn roster(&self, patterns: &AttackPatterns) -> BTreeMap<EnemyKind, EnemyRules> {
+        self.kinds
+            .iter()
+            .filter_map(|(kind, archetype)| {
+                let Some(pattern) = patterns.get(&archetype.pattern) else {
+                    println!("Unknown attack pattern {}.", archetype.pattern);
+                    return None;
+                };
+                let rules = EnemyRules {
+                    pattern: pattern.clone(),
+                    movement: archetype.movement,
+                    health: archetype.health.max(1),
+                };
+                Some((*kind, rules))
+            })
+            .collect()
+    }
+
+    // Weighted pick, the map is sorted so the same seed picks the same kinds.
+    pub fn pick(&self, rng: &mut impl Rng) -> Option<EnemyKind> {
+        let weights: Vec<(EnemyKind, u32)> = self
+            .kinds
+            .iter()
+            .map(|(kind, archetype)| (*kind, archetype.spawn_weight))
+            .collect();
+        pick_weighted(&weights, rng)
+    }
+}
+
+// Picks a kind with a chance relative to its weight, None when every weight is 0.
+pub fn pick_weighted(weights: &[(EnemyKind, u32)], rng: &mut impl Rng) -> Option<EnemyKind> {
+    let total: u32 = weights.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.random_range(0..total);
+    for (kind, weight) in weights {
+        if roll < *weight {
+            return Some(*kind);
+        }
+        roll -= weight;
+    }
+    None
+}
+
+#[derive(Resource)]
+pub struct EnemyKindsHandle(pub Handle<EnemyKinds>);
+
+pub struct EnemyKindsPlugin;
+
+impl Plugin for EnemyKindsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<EnemyKinds>()
+            .init_asset_loader::<RonAssetLoader<EnemyKinds>>()
+            .add_systems(Startup, load_enemy_kinds)
+            .add_systems(Update, log_enemy_kind_reloads);
+    }
+}
+
+fn load_enemy_kinds(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(EnemyKindsHandle(asset_server.load(ENEMY_KINDS_PATH)));
+}
+
+// Stats are read on every step, sprites only apply to enemies spawned after the edit.
+fn log_enemy_kind_reloads(mut events: MessageReader<AssetEvent<EnemyKinds>>) {
+    for event in events.read() {
+        if let AssetEvent::Modified { .. } = event {
+            println!("Enemy kinds reloaded.");
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::constants::*;
+
+// Written for every hit a unit takes, health is what's left after it, 0 when it died.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DamageTaken {
+    pub entity: Entity,
+    pub health: u32,
+}
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<DamageTaken>().add_systems(
+            Update,
+            (
+                (
+                    grant_player_invulnerability,
+                    expire_invulnerability,
+                    blink_invulnerable,
+                )
+                    .chain()
+                    .run_if(in_state(ApplicationState::Gameplay)),
+                animate_dying,
+            ),
+        );
+    }
+}
+
+// A player that survives a hit gets a few turns to get out of the way.
+fn grant_player_invulnerability(
+    mut commands: Commands,
+    mut damage_taken: MessageReader<DamageTaken>,
+    turn_count: Res<TurnCount>,
+    player_q: Query<(), With<Player>>,
+) {
+    for damage in damage_taken.read() {
+        if damage.health > 0 && player_q.contains(damage.entity) {
+            commands.entity(damage.entity).insert(Invulnerable {
+                until_turn: turn_count.0 + PLAYER_INVULNERABLE_TURNS,
+            });
+        }
+    }
+}
+
+fn expire_invulnerability(
+    mut commands: Commands,
+    turn_count: Res<TurnCount>,
+    mut invulnerable_q: Query<(Entity, &Invulnerable, &mut Visibility)>,
+) {
+    for (entity, invulnerable, mut visibility) in invulnerable_q.iter_mut() {
+        if invulnerable.until_turn < turn_count.0 {
+            commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+// Blinking sprite so the player can see the hit landed and that it's safe for now.
+fn blink_invulnerable(
+    time: Res<Time>,
+    mut invulnerable_q: Query<&mut Visibility, With<Invulnerable>>,
+) {
+    let blink = (time.elapsed_secs() / INVULNERABLE_BLINK_SECONDS) as u32;
+    for mut visibility in invulnerable_q.iter_mut() {
+        *visibility = if blink.is_multiple_of(2) {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+    }
+}
+
+// Killed units shrink and spin away, then get despawned.
+fn animate_dying(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut dying_q: Query<(Entity, &mut Dying, Option<&mut Transform>)>,
+) {
+    for (entity, mut dying, transform) in dying_q.iter_mut() {
+        dying.tick(time.delta());
+        if dying.is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(mut transform) = transform {
+            let left = 1.0 - dying.fraction();
+            transform.scale = Vec3::splat(left);
+            transform.rotation = Quat::from_rotation_z(dying.fraction() * std::f32::consts::TAU);
+        }
+    }
+}
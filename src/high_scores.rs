@@ -0,0 +1,253 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::data_files::{data_file, save_ron};
+use crate::replay::ReplayPlayback;
+use crate::stats::RunStats;
+
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const MAX_NAME_LENGTH: usize = 12;
+const DEFAULT_NAME: &str = "Wizard";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub turns: u32,
+    pub seed: u64,
+    pub date: String,
+}
+
+// Best runs so far, highest score first.
+#[derive(Resource, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+}
+
+impl HighScores {
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.iter().any(|entry| entry.score < score))
+    }
+
+    // Keeps the table sorted and at most MAX_HIGH_SCORES long, returns where the entry ended up.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let index = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        if index >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(index)
+    }
+
+    // A missing or broken file is an empty table, the game shouldn't fail to start over it.
+    pub fn load(path: &Path) -> HighScores {
+        let Ok(text) = fs::read_to_string(path) else {
+            return HighScores::default();
+        };
+        ron::from_str(&text).unwrap_or_else(|error| {
+            println!(
+                "Couldn't read high scores from {}: {}",
+                path.display(),
+                error
+            );
+            HighScores::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BevyError> {
+        save_ron(path, self)
+    }
+}
+
+// Where the high scores are saved.
+#[derive(Resource, Debug, Clone)]
+pub struct HighScorePath(pub PathBuf);
+
+impl Default for HighScorePath {
+    fn default() -> Self {
+        HighScorePath(data_file("high_scores.ron"))
+    }
+}
+
+// A run that made it into the table, waiting for the player to type a name.
+#[derive(Resource, Debug, Clone)]
+pub struct NameEntry {
+    pub name: String,
+    pub entry: HighScoreEntry,
+}
+
+#[derive(Component)]
+pub struct NameEntryText;
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScorePath>()
+            .init_resource::<HighScores>()
+            .add_systems(Startup, load_high_scores)
+            // Watched replays don't set high scores.
+            .add_systems(
+                OnEnter(ApplicationState::EndMenu),
+                start_name_entry.run_if(not(resource_exists::<ReplayPlayback>)),
+            )
+            .add_systems(
+                Update,
+                name_entry_system
+                    .run_if(in_state(ApplicationState::EndMenu))
+                    .run_if(resource_exists::<NameEntry>),
+            )
+            // Leaving the end screen without pressing enter still keeps the record.
+            .add_systems(OnExit(ApplicationState::EndMenu), save_name_entry);
+    }
+}
+
+fn load_high_scores(path: Res<HighScorePath>, mut high_scores: ResMut<HighScores>) {
+    *high_scores = HighScores::load(&path.0);
+}
+
+fn start_name_entry(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    stats: Res<RunStats>,
+    rng: Res<GameRng>,
+    high_scores: Res<HighScores>,
+) {
+    if !high_scores.qualifies(stats.score) {
+        return;
+    }
+
+    commands.insert_resource(NameEntry {
+        name: String::new(),
+        entry: HighScoreEntry {
+            name: String::new(),
+            score: stats.score,
+            turns: stats.turns_survived,
+            seed: rng.seed(),
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+        },
+    });
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: percent(10),
+            width: percent(100),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(ApplicationState::EndMenu),
+        children![(
+            NameEntryText,
+            Text::new(name_prompt("")),
+            TextFont {
+                font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+                font_size: 32.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+        )],
+    ));
+}
+
+fn name_prompt(name: &str) -> String {
+    format!("New high score! Type your name: {}_", name)
+}
+
+// Letters, digits and spaces, enter saves the record.
+fn name_entry_system(
+    mut commands: Commands,
+    mut keyboard_input: MessageReader<KeyboardInput>,
+    mut name_entry: ResMut<NameEntry>,
+    path: Res<HighScorePath>,
+    mut high_scores: ResMut<HighScores>,
+    mut text_q: Query<&mut Text, With<NameEntryText>>,
+) {
+    for input in keyboard_input.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &input.logical_key {
+            Key::Enter => {
+                let entry = take_entry(&name_entry);
+                save_entry(entry, &path, &mut high_scores);
+                commands.remove_resource::<NameEntry>();
+                for mut text in &mut text_q {
+                    text.0 = String::from("Saved!");
+                }
+                return;
+            }
+            Key::Backspace => {
+                name_entry.name.pop();
+            }
+            Key::Space if name_entry.name.len() < MAX_NAME_LENGTH => {
+                name_entry.name.push(' ');
+            }
+            Key::Character(text) => {
+                for character in text.chars() {
+                    if character.is_alphanumeric() && name_entry.name.len() < MAX_NAME_LENGTH {
+                        name_entry.name.push(character);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if name_entry.is_changed() {
+        for mut text in &mut text_q {
+            text.0 = name_prompt(&name_entry.name);
+        }
+    }
+}
+
+fn save_name_entry(
+    mut commands: Commands,
+    name_entry: Option<Res<NameEntry>>,
+    path: Res<HighScorePath>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let Some(name_entry) = name_entry else {
+        return;
+    };
+    save_entry(take_entry(&name_entry), &path, &mut high_scores);
+    commands.remove_resource::<NameEntry>();
+}
+
+fn take_entry(name_entry: &NameEntry) -> HighScoreEntry {
+    let name = name_entry.name.trim();
+    HighScoreEntry {
+        name: if name.is_empty() {
+            DEFAULT_NAME.to_string()
+        } else {
+            name.to_string()
+        },
+        ..name_entry.entry.clone()
+    }
+}
+
+fn save_entry(entry: HighScoreEntry, path: &HighScorePath, high_scores: &mut HighScores) {
+    high_scores.insert(entry);
+    if let Err(error) = high_scores.save(&path.0) {
+        println!(
+            "Couldn't save high scores to {}: {}",
+            path.0.display(),
+            error
+        );
+    }
+}
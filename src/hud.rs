@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use crate::attack_patterns::*;
+use crate::components::*;
+use crate::constants::*;
+use crate::input::{InputAction, KeyBindings, key_name};
+use crate::undo::UndosLeft;
+use crate::waves::*;
+
+#[derive(Component)]
+pub struct AbilityHud;
+
+#[derive(Component)]
+pub struct WaveHud;
+
+fn hud_font(asset_server: &AssetServer) -> TextFont {
+    TextFont {
+        font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+        font_size: 20.0,
+        ..default()
+    }
+}
+
+pub fn setup_ability_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        AbilityHud,
+        Text::new(""),
+        hud_font(&asset_server),
+        TextColor(Color::WHITE),
+        TextShadow::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(20),
+            left: px(20),
+            ..default()
+        },
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
+}
+
+// Lives, moves left, charges, undos and every attack with its cooldown or the charges it still needs.
+pub fn update_ability_hud(
+    abilities: Res<PlayerAbilities>,
+    action_points: Res<ActionPoints>,
+    undos: Res<UndosLeft>,
+    bindings: Res<KeyBindings>,
+    patterns_handle: Res<AttackPatternsHandle>,
+    patterns: Res<Assets<AttackPatterns>>,
+    player_q: Query<&Health, With<Player>>,
+    mut hud_q: Query<&mut Text, With<AbilityHud>>,
+) {
+    let Some(patterns) = patterns.get(&patterns_handle.0) else {
+        return;
+    };
+
+    let lives = player_q.single().map_or(0, |health| health.0);
+    let mut lines = vec![
+        format!("Lives: {}/{}", lives, PLAYER_LIVES),
+        format!("Moves: {}", action_points.0),
+        format!("Charges: {}", abilities.charges),
+        format!("Undos: {}/{}", undos.0, UNDOS_PER_RUN),
+    ];
+    for (slot, action) in InputAction::ATTACKS.iter().enumerate() {
+        // Read every frame, a rebound key shows up right away.
+        let key = bindings
+            .keys(*action)
+            .first()
+            .map_or(String::from("-"), |key| key_name(*key));
+        let (Some(id), Some(pattern)) = (patterns.player.get(slot), patterns.player_slot(slot))
+        else {
+            continue;
+        };
+
+        let cooldown = abilities.cooldown(slot);
+        let status = if cooldown > 0 {
+            format!("{} turns", cooldown)
+        } else if abilities.charges < pattern.cost {
+            format!("needs {} charges", pattern.cost)
+        } else {
+            String::from("ready")
+        };
+        lines.push(format!("{} {}: {}", key, id, status));
+    }
+
+    for mut text in &mut hud_q {
+        text.0 = lines.join("\n");
+    }
+}
+
+pub fn setup_wave_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        WaveHud,
+        Text::new(""),
+        hud_font(&asset_server),
+        TextColor(Color::WHITE),
+        TextShadow::default(),
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(20),
+            right: px(20),
+            ..default()
+        },
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
+}
+
+// Current wave and how many enemies are still coming, or the turns until the next wave.
+pub fn update_wave_hud(
+    director: Res<WaveDirector>,
+    script_handle: Res<WaveScriptHandle>,
+    scripts: Res<Assets<WaveScript>>,
+    mut hud_q: Query<&mut Text, With<WaveHud>>,
+) {
+    let Some(script) = scripts.get(&script_handle.0) else {
+        return;
+    };
+
+    let wave = format!("Wave {}/{}", director.wave + 1, script.waves.len());
+    let status = if let Some(turns) = director.turns_until_wave() {
+        let unit = if turns == 1 { "turn" } else { "turns" };
+        format!("Next wave in {} {}", turns, unit)
+    } else if director.is_wave_out() {
+        String::from("Clear the wave")
+    } else {
+        format!("{} enemies coming", director.size - director.spawned)
+    };
+
+    for mut text in &mut hud_q {
+        text.0 = format!("{}\n{}", wave, status);
+    }
+}
@@ -1,8 +1,324 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::input::InputSystems;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::ApplicationState;
+use crate::constants::STICK_DEADZONE;
+use crate::data_files::{config_file, save_ron};
+use crate::rules::MoveDirection;
+
+// Everything the player can do, the game reads these instead of the keys.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InputAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    EndMove,
+    Attack1,
+    Attack2,
+    Attack3,
+    Attack4,
+    Pause,
+    Undo,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 11] = [
+        InputAction::MoveUp,
+        InputAction::MoveDown,
+        InputAction::MoveLeft,
+        InputAction::MoveRight,
+        InputAction::EndMove,
+        InputAction::Attack1,
+        InputAction::Attack2,
+        InputAction::Attack3,
+        InputAction::Attack4,
+        InputAction::Pause,
+        InputAction::Undo,
+    ];
+
+    pub const ATTACKS: [InputAction; 4] = [
+        InputAction::Attack1,
+        InputAction::Attack2,
+        InputAction::Attack3,
+        InputAction::Attack4,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InputAction::MoveUp => "Move Up",
+            InputAction::MoveDown => "Move Down",
+            InputAction::MoveLeft => "Move Left",
+            InputAction::MoveRight => "Move Right",
+            InputAction::EndMove => "End Move",
+            InputAction::Attack1 => "Attack 1",
+            InputAction::Attack2 => "Attack 2",
+            InputAction::Attack3 => "Attack 3",
+            InputAction::Attack4 => "Attack 4",
+            InputAction::Pause => "Pause",
+            InputAction::Undo => "Undo",
+        }
+    }
+
+    pub fn moving(direction: MoveDirection) -> InputAction {
+        match direction {
+            MoveDirection::Up => InputAction::MoveUp,
+            MoveDirection::Down => InputAction::MoveDown,
+            MoveDirection::Left => InputAction::MoveLeft,
+            MoveDirection::Right => InputAction::MoveRight,
+        }
+    }
+}
+
+// Written when an action changed the game, however it was given, replays are recorded from these.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActionTaken(pub InputAction);
+
+// Short name for the help window and the HUD, KeyQ is shown as Q.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+// Short name for the help window, DPadUp is shown as Pad DPadUp.
+pub fn button_name(button: GamepadButton) -> String {
+    format!("Pad {:?}", button)
+}
+
+// Keys and gamepad buttons bound to every action, saved in the config directory when changed.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    pub keys: BTreeMap<InputAction, Vec<KeyCode>>,
+    #[serde(default)]
+    pub buttons: BTreeMap<InputAction, Vec<GamepadButton>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = [
+            (InputAction::MoveUp, KeyCode::ArrowUp),
+            (InputAction::MoveDown, KeyCode::ArrowDown),
+            (InputAction::MoveLeft, KeyCode::ArrowLeft),
+            (InputAction::MoveRight, KeyCode::ArrowRight),
+            (InputAction::EndMove, KeyCode::Space),
+            (InputAction::Attack1, KeyCode::KeyQ),
+            (InputAction::Attack2, KeyCode::KeyW),
+            (InputAction::Attack3, KeyCode::KeyE),
+            (InputAction::Attack4, KeyCode::KeyR),
+            (InputAction::Pause, KeyCode::Escape),
+            (InputAction::Undo, KeyCode::KeyZ),
+        ]
+        .into_iter()
+        .map(|(action, key)| (action, vec![key]))
+        .collect();
+        let buttons = [
+            (InputAction::MoveUp, GamepadButton::DPadUp),
+            (InputAction::MoveDown, GamepadButton::DPadDown),
+            (InputAction::MoveLeft, GamepadButton::DPadLeft),
+            (InputAction::MoveRight, GamepadButton::DPadRight),
+            (InputAction::EndMove, GamepadButton::RightTrigger),
+            (InputAction::Attack1, GamepadButton::West),
+            (InputAction::Attack2, GamepadButton::North),
+            (InputAction::Attack3, GamepadButton::East),
+            (InputAction::Attack4, GamepadButton::South),
+            (InputAction::Pause, GamepadButton::Start),
+            (InputAction::Undo, GamepadButton::Select),
+        ]
+        .into_iter()
+        .map(|(action, button)| (action, vec![button]))
+        .collect();
+        KeyBindings { keys, buttons }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: InputAction) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // The key only does one thing, it's taken away from whatever action had it before.
+    pub fn bind(&mut self, action: InputAction, key: KeyCode) {
+        for keys in self.keys.values_mut() {
+            keys.retain(|other| *other != key);
+        }
+        self.keys.insert(action, vec![key]);
+    }
+
+    pub fn buttons(&self, action: InputAction) -> &[GamepadButton] {
+        self.buttons.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    // Same as bind, for a gamepad button.
+    pub fn bind_button(&mut self, action: InputAction, button: GamepadButton) {
+        for buttons in self.buttons.values_mut() {
+            buttons.retain(|other| *other != button);
+        }
+        self.buttons.insert(action, vec![button]);
+    }
 
-pub fn process_keyboard(keys: Res<ButtonInput<KeyCode>>) {
-    // Exit the application.
-    if keys.just_pressed(KeyCode::Escape) {
-        std::process::exit(0);
+    // Actions missing from the file keep their default keys, a broken file is all defaults.
+    pub fn load(path: &Path) -> KeyBindings {
+        let mut bindings = KeyBindings::default();
+        let Ok(text) = fs::read_to_string(path) else {
+            return bindings;
+        };
+        match ron::from_str::<KeyBindings>(&text) {
+            Ok(loaded) => {
+                bindings.keys.extend(loaded.keys);
+                bindings.buttons.extend(loaded.buttons);
+            }
+            Err(error) => println!("Couldn't read key bindings from {}: {}", path.display(), error),
+        }
+        bindings
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BevyError> {
+        save_ron(path, self)
+    }
+}
+
+// Where the key bindings are saved.
+#[derive(Resource, Debug, Clone)]
+pub struct KeyBindingsPath(pub PathBuf);
+
+impl Default for KeyBindingsPath {
+    fn default() -> Self {
+        KeyBindingsPath(config_file("keybindings.ron"))
+    }
+}
+
+// Action waiting for a key from the rebinding window, gameplay ignores the keys until it has one.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebinding(pub Option<InputAction>);
+
+pub struct InputActionsPlugin;
+
+impl Plugin for InputActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KeyBindingsPath>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<ButtonInput<InputAction>>()
+            .init_resource::<Rebinding>()
+            .add_message::<ActionTaken>()
+            .add_systems(Startup, load_key_bindings)
+            .add_systems(
+                PreUpdate,
+                (update_actions, rebind_key).chain().after(InputSystems),
+            );
+    }
+}
+
+fn load_key_bindings(path: Res<KeyBindingsPath>, mut bindings: ResMut<KeyBindings>) {
+    *bindings = KeyBindings::load(&path.0);
+}
+
+// Next key or gamepad button pressed while rebinding goes to the action and the bindings are saved right away.
+fn rebind_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<KeyBindings>,
+    path: Res<KeyBindingsPath>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+
+    if let Some(&key) = keys.get_just_pressed().next() {
+        bindings.bind(action, key);
+    } else if let Some(&button) = gamepads
+        .iter()
+        .find_map(|gamepad| gamepad.digital().get_just_pressed().next())
+    {
+        bindings.bind_button(action, button);
+    } else {
+        return;
+    }
+    rebinding.0 = None;
+    if let Err(error) = bindings.save(&path.0) {
+        println!("Couldn't save key bindings to {}: {}", path.0.display(), error);
+    }
+}
+
+// Move action the left stick is pushed towards, whichever axis is pushed further wins.
+pub fn stick_action(stick: Vec2) -> Option<InputAction> {
+    if stick.length() < STICK_DEADZONE {
+        None
+    } else if stick.x.abs() > stick.y.abs() {
+        Some(if stick.x > 0.0 {
+            InputAction::MoveRight
+        } else {
+            InputAction::MoveLeft
+        })
+    } else {
+        Some(if stick.y > 0.0 {
+            InputAction::MoveUp
+        } else {
+            InputAction::MoveDown
+        })
+    }
+}
+
+// An action is pressed with any of its keys, buttons or the stick and held until all of them are released.
+// Only new presses count, a key still held from rebinding doesn't fire its new action,
+// and a stick held in one direction moves one tile until it goes back to the middle.
+fn update_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    mut actions: ResMut<ButtonInput<InputAction>>,
+) {
+    actions.clear();
+    for action in InputAction::ALL {
+        let bound_keys = bindings.keys(action).iter().copied();
+        let bound_buttons = bindings.buttons(action).iter().copied();
+        let stick = gamepads
+            .iter()
+            .any(|gamepad| stick_action(gamepad.left_stick()) == Some(action));
+        let held = keys.any_pressed(bound_keys.clone())
+            || stick
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.any_pressed(bound_buttons.clone()));
+        let just_pressed = keys.any_just_pressed(bound_keys)
+            || (stick && !actions.pressed(action))
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.any_just_pressed(bound_buttons.clone()));
+
+        if rebinding.0.is_some() || !held {
+            actions.release(action);
+        } else if just_pressed {
+            actions.press(action);
+        }
+    }
+}
+
+// Pause leaves the game from the menus, in gameplay it opens the pause menu instead.
+// The end screen goes back to the main menu first, leaving it saves a pending high score.
+pub fn process_keyboard(
+    actions: Res<ButtonInput<InputAction>>,
+    state: Res<State<ApplicationState>>,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    if !actions.just_pressed(InputAction::Pause) {
+        return;
+    }
+    match state.get() {
+        ApplicationState::Gameplay => {}
+        ApplicationState::EndMenu => next_state.set(ApplicationState::MainMenu),
+        _ => {
+            app_exit.write(AppExit::Success);
+        }
     }
 }
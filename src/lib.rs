@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+pub mod attack_patterns;
+pub mod button_2d;
+pub mod camera;
+pub mod characters;
+pub mod components;
+pub mod constants;
+pub mod data_files;
+pub mod editor;
+pub mod enemy_ai;
+pub mod enemy_kinds;
+pub mod health;
+pub mod high_scores;
+pub mod hud;
+pub mod input;
+pub mod menu;
+pub mod pause;
+pub mod playzone;
+pub mod pointer;
+pub mod post_processing;
+pub mod replay;
+pub mod ron_asset;
+pub mod rules;
+pub mod save_game;
+pub mod special_tiles;
+pub mod startup;
+pub mod stats;
+pub mod telegraph;
+pub mod text_2d;
+pub mod troop_utilities;
+pub mod undo;
+pub mod update;
+pub mod utilities;
+pub mod waves;
+
+use crate::attack_patterns::AttackPatternsPlugin;
+use crate::characters::CharactersPlugin;
+use crate::enemy_kinds::EnemyKindsPlugin;
+use crate::components::{ApplicationState, GameRng};
+use crate::health::HealthPlugin;
+use crate::high_scores::HighScoresPlugin;
+use crate::hud::*;
+use crate::input::InputActionsPlugin;
+use crate::pause::PausePlugin;
+use crate::playzone::PlayzonePlugin;
+use crate::pointer::PointerPlugin;
+use crate::replay::ReplayPlugin;
+use crate::save_game::SaveGamePlugin;
+use crate::special_tiles::SpecialTilesPlugin;
+use crate::startup::*;
+use crate::stats::StatsPlugin;
+use crate::telegraph::TelegraphPlugin;
+use crate::text_2d::*;
+use crate::troop_utilities::*;
+use crate::undo::UndoPlugin;
+use crate::update::*;
+use crate::utilities::update_animated_sprites;
+use crate::waves::WavePlugin;
+
+// Everything the game needs that doesn't touch the window or the renderer.
+// The binary adds the rendering, menus and egui on top, the tests run it with MinimalPlugins.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        // The binary and the tests can insert a seeded one before adding the plugin.
+        if !app.world().contains_resource::<GameRng>() {
+            app.insert_resource(GameRng::from_entropy());
+        }
+
+        app
+            // STATES
+            .init_state::<ApplicationState>()
+            // INPUT
+            .add_plugins((InputActionsPlugin, PointerPlugin))
+            .add_plugins((TurnPlugin, PausePlugin))
+            // ASSETS
+            .add_plugins((
+                AttackPatternsPlugin,
+                CharactersPlugin,
+                EnemyKindsPlugin,
+                PlayzonePlugin,
+                WavePlugin,
+            ))
+            // UNITS
+            .add_plugins((HealthPlugin, TelegraphPlugin, SpecialTilesPlugin))
+            // SCORE
+            .add_plugins((StatsPlugin, HighScoresPlugin))
+            // SAVES
+            .add_plugins((SaveGamePlugin, UndoPlugin, ReplayPlugin))
+            // GAMEPLAY
+            .add_systems(
+                OnEnter(ApplicationState::Gameplay),
+                (
+                    start_run,
+                    setup_scene,
+                    setup_font,
+                    setup_ability_hud,
+                    setup_wave_hud,
+                ),
+            )
+            // UPDATE
+            .add_systems(
+                Update,
+                (
+                    (
+                        update_player_troop_to_tilemap,
+                        update_enemy_troop_to_tilemap,
+                        render_rotated_text,
+                        update_ability_hud,
+                        update_wave_hud,
+                    )
+                        .run_if(in_state(ApplicationState::Gameplay)),
+                    update_animated_sprites,
+                ),
+            );
+    }
+}
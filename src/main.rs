@@ -5,30 +5,19 @@ use bevy_ecs_tiled::prelude::*;
 use bevy_egui::EguiPlugin;
 use bevy_egui::EguiPrimaryContextPass;
 
-pub mod button_2d;
-pub mod camera;
-pub mod components;
-pub mod constants;
-pub mod editor;
-pub mod input;
-pub mod post_processing;
-pub mod startup;
-pub mod text_2d;
-pub mod troop_utilities;
-pub mod update;
-pub mod utilities;
-
-use crate::camera::*;
-use crate::editor::*;
-use crate::input::*;
-use crate::post_processing::*;
-use crate::startup::*;
-use crate::text_2d::*;
-use crate::troop_utilities::*;
-use crate::update::*;
-use crate::utilities::update_animated_sprites;
+use global_game_jam_2026::GamePlugin;
+use global_game_jam_2026::button_2d::*;
+use global_game_jam_2026::camera::*;
+use global_game_jam_2026::components::{ApplicationState, GameRng};
+use global_game_jam_2026::editor::*;
+use global_game_jam_2026::input::*;
+use global_game_jam_2026::menu::*;
+use global_game_jam_2026::post_processing::*;
 
 fn main() {
+    let rng = GameRng::from_env();
+    println!("Seed: {}", rng.seed());
+
     App::new()
         // PLUGINS
         .add_plugins((
@@ -53,23 +42,26 @@ fn main() {
         .add_plugins(TilemapPlugin)
         .add_plugins(TiledPlugin::default())
         .add_plugins(EguiPlugin::default())
+        .insert_resource(rng)
+        .add_plugins(GamePlugin)
         // STARTUP
-        .add_systems(Startup, (setup_scene, setup_font))
+        .add_systems(Startup, setup_camera)
+        // MENUS
+        .add_systems(OnEnter(ApplicationState::MainMenu), setup_main_menu)
+        .add_systems(OnEnter(ApplicationState::EndMenu), setup_end_menu)
+        .add_systems(OnEnter(ApplicationState::Scoreboard), setup_scoreboard)
         // UPDATE
         .add_systems(
             Update,
             (
                 process_keyboard,
                 update_camera,
-                update_game_logic,
-                update_player_troop_to_tilemap,
-                update_enemy_troop_to_tilemap,
-                update_animated_sprites,
-                render_rotated_text,
+                button_system,
+                button_action_system,
                 render_post_processing,
             ),
         )
         // EGUI
-        .add_systems(EguiPrimaryContextPass, render_egui)
+        .add_systems(EguiPrimaryContextPass, (render_egui, render_replay_panel))
         .run();
 }
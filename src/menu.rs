@@ -0,0 +1,190 @@
+use crate::button_2d::button;
+use crate::components::*;
+use crate::high_scores::HighScores;
+use crate::playzone::PlayzoneMap;
+use crate::save_game::{ResumeRun, SaveGame, SavePath};
+use crate::stats::RunStats;
+use bevy::prelude::*;
+
+// Full screen column that centers the menu content.
+fn menu_root() -> Node {
+    Node {
+        width: percent(100),
+        height: percent(100),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        row_gap: px(20),
+        ..default()
+    }
+}
+
+fn menu_title(asset_server: &AssetServer, title: &str) -> impl Bundle {
+    (
+        Text::new(title),
+        TextFont {
+            font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+            font_size: 64.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextShadow::default(),
+    )
+}
+
+fn run_summary(asset_server: &AssetServer, stats: &RunStats) -> impl Bundle {
+    (
+        Text::new(stats.summary().join("\n")),
+        TextFont {
+            font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextLayout::new_with_justify(Justify::Center),
+    )
+}
+
+pub fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    save_path: Res<SavePath>,
+) {
+    commands
+        .spawn((menu_root(), DespawnOnExit(ApplicationState::MainMenu)))
+        .with_children(|parent| {
+            parent.spawn(menu_title(&asset_server, "Color Wizard"));
+            // Only offered when there is a run to continue.
+            if save_path.exists() {
+                parent.spawn(button(&asset_server, "Continue", ButtonAction::Continue));
+            }
+            parent.spawn(button(&asset_server, "Play", ButtonAction::Play));
+            parent.spawn(button(&asset_server, "Scores", ButtonAction::Scoreboard));
+            parent.spawn(button(&asset_server, "Quit", ButtonAction::Quit));
+        });
+}
+
+pub fn setup_end_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    outcome: Res<RunOutcome>,
+    stats: Res<RunStats>,
+) {
+    let title = match *outcome {
+        RunOutcome::Defeat => "Game Over",
+        RunOutcome::Victory => "Victory",
+    };
+
+    commands.spawn((
+        menu_root(),
+        DespawnOnExit(ApplicationState::EndMenu),
+        children![
+            menu_title(&asset_server, title),
+            run_summary(&asset_server, &stats),
+            button(&asset_server, "Restart", ButtonAction::Restart),
+            button(&asset_server, "Quit", ButtonAction::Quit),
+        ],
+    ));
+}
+
+pub fn setup_scoreboard(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    high_scores: Res<HighScores>,
+) {
+    let lines = if high_scores.entries.is_empty() {
+        String::from("No runs yet")
+    } else {
+        high_scores
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                format!(
+                    "{}. {}  {}  {} turns  seed {}  {}",
+                    index + 1,
+                    entry.name,
+                    entry.score,
+                    entry.turns,
+                    entry.seed,
+                    entry.date
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    commands.spawn((
+        menu_root(),
+        DespawnOnExit(ApplicationState::Scoreboard),
+        children![
+            menu_title(&asset_server, "High Scores"),
+            (
+                Text::new(lines),
+                TextFont {
+                    font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                TextLayout::new_with_justify(Justify::Center),
+            ),
+            button(&asset_server, "Back", ButtonAction::Back),
+        ],
+    ));
+}
+
+// Reacts to pressed menu buttons, the scene itself is torn down and respawned by the state changes.
+pub fn button_action_system(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    save_path: Res<SavePath>,
+    playzone_map: PlayzoneMap,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    mut app_exit: MessageWriter<AppExit>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let starts_run = matches!(
+            action,
+            ButtonAction::Continue | ButtonAction::Play | ButtonAction::Restart
+        );
+        if starts_run && !playzone_map.is_ready() {
+            println!("The playzone is still loading.");
+            continue;
+        }
+
+        match action {
+            ButtonAction::Continue => {
+                let Some(save) = SaveGame::load(&save_path.0) else {
+                    continue;
+                };
+                commands.insert_resource(ResumeRun(save));
+                next_state.set(ApplicationState::Gameplay);
+            }
+            ButtonAction::Play | ButtonAction::Restart => {
+                next_state.set(ApplicationState::Gameplay);
+            }
+            ButtonAction::Scoreboard => {
+                next_state.set(ApplicationState::Scoreboard);
+            }
+            ButtonAction::Back => {
+                next_state.set(ApplicationState::MainMenu);
+            }
+            ButtonAction::Quit => {
+                app_exit.write(AppExit::Success);
+            }
+            // The pause menu has its own system, see pause.rs.
+            ButtonAction::Resume
+            | ButtonAction::Pause
+            | ButtonAction::Settings
+            | ButtonAction::Fullscreen
+            | ButtonAction::SaveAndQuit
+            | ButtonAction::QuitToMenu
+            | ButtonAction::ConfirmQuit => {}
+        }
+    }
+}
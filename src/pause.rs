@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+
+use crate::button_2d::button;
+use crate::components::*;
+use crate::input::InputAction;
+use crate::save_game::RunSaver;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PauseState>()
+            // Timers run on virtual time, pausing it freezes every turn phase where it is.
+            .add_systems(OnExit(PauseState::Running), pause_time)
+            .add_systems(OnEnter(PauseState::Running), unpause_time)
+            .add_systems(OnExit(ApplicationState::Gameplay), unpause_time)
+            .add_systems(OnEnter(PauseState::Paused), setup_pause_menu)
+            .add_systems(OnEnter(PauseState::Settings), setup_settings_menu)
+            .add_systems(
+                OnEnter(PauseState::ConfirmQuit(QuitTarget::Desktop)),
+                setup_confirm_quit_menu,
+            )
+            .add_systems(
+                OnEnter(PauseState::ConfirmQuit(QuitTarget::Menu)),
+                setup_confirm_quit_menu,
+            )
+            .add_systems(
+                Update,
+                (toggle_pause, pause_button_system).run_if(in_state(ApplicationState::Gameplay)),
+            );
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn toggle_pause(
+    actions: Res<ButtonInput<InputAction>>,
+    state: Res<State<PauseState>>,
+    mut next_state: ResMut<NextState<PauseState>>,
+) {
+    if !actions.just_pressed(InputAction::Pause) {
+        return;
+    }
+
+    next_state.set(match state.get() {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+        PauseState::Settings | PauseState::ConfirmQuit(_) => PauseState::Paused,
+    });
+}
+
+// Dark overlay on top of the playzone with the content in a centered column.
+fn overlay_root() -> impl Bundle {
+    (
+        Node {
+            position_type: PositionType::Absolute,
+            width: percent(100),
+            height: percent(100),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: px(20),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.6)),
+    )
+}
+
+fn overlay_title(asset_server: &AssetServer, title: &str) -> impl Bundle {
+    (
+        Text::new(title),
+        TextFont {
+            font: asset_server.load("fonts/SNPro-VariableFont_wght.ttf"),
+            font_size: 64.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        TextShadow::default(),
+    )
+}
+
+fn setup_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        overlay_root(),
+        DespawnOnExit(PauseState::Paused),
+        children![
+            overlay_title(&asset_server, "Paused"),
+            button(&asset_server, "Resume", ButtonAction::Resume),
+            button(&asset_server, "Settings", ButtonAction::Settings),
+            button(&asset_server, "Save & Quit", ButtonAction::SaveAndQuit),
+            button(&asset_server, "Quit to menu", ButtonAction::QuitToMenu),
+        ],
+    ));
+}
+
+fn setup_settings_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        overlay_root(),
+        DespawnOnExit(PauseState::Settings),
+        children![
+            overlay_title(&asset_server, "Settings"),
+            button(&asset_server, "Fullscreen", ButtonAction::Fullscreen),
+            button(&asset_server, "Back", ButtonAction::Pause),
+        ],
+    ));
+}
+
+fn setup_confirm_quit_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<PauseState>>,
+) {
+    let title = match state.get() {
+        PauseState::ConfirmQuit(QuitTarget::Desktop) => "Save and quit?",
+        _ => "Quit to menu?",
+    };
+    commands.spawn((
+        overlay_root(),
+        DespawnOnExit(*state.get()),
+        children![
+            overlay_title(&asset_server, title),
+            button(&asset_server, "Yes", ButtonAction::ConfirmQuit),
+            button(&asset_server, "No", ButtonAction::Pause),
+        ],
+    ));
+}
+
+// The run is autosaved whenever a turn phase starts waiting, so leaving never loses more than
+// the phase that is playing out. Save and quit saves it once more on the way out.
+// Continue in the main menu picks it up again.
+fn pause_button_system(
+    interaction_query: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    mut next_state: ResMut<NextState<ApplicationState>>,
+    mut app_exit: MessageWriter<AppExit>,
+    mut window_q: Query<&mut Window>,
+    saver: RunSaver,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match action {
+            ButtonAction::Resume => next_pause_state.set(PauseState::Running),
+            ButtonAction::Pause => next_pause_state.set(PauseState::Paused),
+            ButtonAction::Settings => next_pause_state.set(PauseState::Settings),
+            ButtonAction::Fullscreen => {
+                for mut window in &mut window_q {
+                    window.mode = match window.mode {
+                        WindowMode::Windowed => WindowMode::Fullscreen(
+                            MonitorSelection::Primary,
+                            VideoModeSelection::Current,
+                        ),
+                        _ => WindowMode::Windowed,
+                    };
+                }
+            }
+            ButtonAction::SaveAndQuit => {
+                next_pause_state.set(PauseState::ConfirmQuit(QuitTarget::Desktop));
+            }
+            ButtonAction::QuitToMenu => {
+                next_pause_state.set(PauseState::ConfirmQuit(QuitTarget::Menu));
+            }
+            ButtonAction::ConfirmQuit => match pause_state.get() {
+                PauseState::ConfirmQuit(QuitTarget::Desktop) => {
+                    saver.save();
+                    app_exit.write(AppExit::Success);
+                }
+                PauseState::ConfirmQuit(QuitTarget::Menu) => {
+                    next_state.set(ApplicationState::MainMenu);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
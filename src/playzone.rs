@@ -0,0 +1,296 @@
+use bevy::asset::LoadState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use tiled::{LayerType, Map, ObjectShape, Properties, PropertyValue};
+
+use crate::components::*;
+use crate::constants::*;
+use crate::rules::{Board, Pos, RoundColor, Terrain};
+use crate::startup::setup_scene;
+
+pub const PLAYZONE_PATH: &str = "Playzone.tmx";
+
+// Enemies of a wave spawn in every zone opened by it or an earlier wave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpawnZone {
+    pub from_wave: usize,
+    pub tiles: Vec<Pos>,
+}
+
+// Tiles that do something when a unit steps on them, from the special property of the tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecialTile {
+    // Kills the enemies while its color is the masked one.
+    ColorPool(RoundColor),
+    // Takes the unit to the other portal with the same pair name.
+    Portal(String),
+    // Lets the player re-roll the masked color, once a run.
+    MaskAltar,
+}
+
+// Layout of the board, authored in Tiled in assets/Playzone.tmx.
+// Tiles with a terrain property are on the Terrain layer, the start and spawn zones are objects.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct Playzone {
+    pub width: u32,
+    pub height: u32,
+    pub player_start: Pos,
+    // Every tile that isn't floor.
+    pub terrain: Vec<(Pos, Terrain)>,
+    pub spawn_zones: Vec<SpawnZone>,
+    pub special: Vec<(Pos, SpecialTile)>,
+}
+
+// The board the game had before it came from Tiled, used when the map is missing.
+impl Default for Playzone {
+    fn default() -> Self {
+        Playzone {
+            width: TILEMAP_SIDE_LENGHT,
+            height: TILEMAP_SIDE_LENGHT,
+            player_start: Pos::new(8, 3),
+            terrain: Vec::new(),
+            spawn_zones: vec![SpawnZone {
+                from_wave: 0,
+                tiles: (0..TILEMAP_SIDE_LENGHT)
+                    .flat_map(|x| (13..15).map(move |y| Pos::new(x, y)))
+                    .collect(),
+            }],
+            special: Vec::new(),
+        }
+    }
+}
+
+impl Playzone {
+    // Tiled counts rows from the top, the tilemap from the bottom.
+    pub fn from_map(map: &Map) -> Playzone {
+        // Without a PlayerStart object the player starts where it did before the map.
+        let mut playzone = Playzone {
+            width: map.width,
+            height: map.height,
+            spawn_zones: Vec::new(),
+            ..Playzone::default()
+        };
+        let tile_size = Vec2::new(map.tile_width as f32, map.tile_height as f32);
+        let to_pos = |x: u32, row: u32| Pos::new(x, map.height - 1 - row);
+
+        for layer in map.layers() {
+            match layer.layer_type() {
+                LayerType::Tiles(tiles) => {
+                    for row in 0..map.height {
+                        for x in 0..map.width {
+                            let Some(tile) = tiles
+                                .get_tile(x as i32, row as i32)
+                                .and_then(|tile| tile.get_tile())
+                            else {
+                                continue;
+                            };
+                            let pos = to_pos(x, row);
+                            if let Some(PropertyValue::StringValue(name)) =
+                                tile.properties.get("terrain")
+                            {
+                                match Terrain::from_name(name) {
+                                    Some(Terrain::Floor) => {}
+                                    Some(terrain) => playzone.terrain.push((pos, terrain)),
+                                    None => println!("Unknown terrain {:?} at {:?}.", name, pos),
+                                }
+                            }
+                            if let Some(special) = special_tile(&tile.properties, pos) {
+                                playzone.special.push((pos, special));
+                            }
+                        }
+                    }
+                }
+                LayerType::Objects(objects) => {
+                    for object in objects.objects() {
+                        let corner = Vec2::new(object.x, object.y) / tile_size;
+                        let size = match object.shape {
+                            ObjectShape::Rect { width, height } => {
+                                Vec2::new(width, height) / tile_size
+                            }
+                            _ => Vec2::ZERO,
+                        };
+                        // Every tile the object overlaps, a point covers the one it's on.
+                        let first = corner.floor().max(Vec2::ZERO).as_uvec2();
+                        let last = (corner + size).ceil().as_uvec2().max(first + UVec2::ONE);
+                        let tiles: Vec<Pos> = (first.x..last.x.min(map.width))
+                            .flat_map(|x| {
+                                (first.y..last.y.min(map.height)).map(move |row| to_pos(x, row))
+                            })
+                            .collect();
+
+                        match object.user_type.as_str() {
+                            "PlayerStart" => {
+                                if let Some(&pos) = tiles.first() {
+                                    playzone.player_start = pos;
+                                }
+                            }
+                            "EnemySpawn" => {
+                                let from_wave = match object.properties.get("from_wave") {
+                                    Some(PropertyValue::IntValue(wave)) => (*wave).max(0) as usize,
+                                    _ => 0,
+                                };
+                                playzone.spawn_zones.push(SpawnZone { from_wave, tiles });
+                            }
+                            other => println!("Unknown playzone object type {:?}.", other),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        playzone
+    }
+
+    pub fn terrain_at(&self, pos: Pos) -> Terrain {
+        self.terrain
+            .iter()
+            .find(|(other, _)| *other == pos)
+            .map(|(_, terrain)| *terrain)
+            .unwrap_or_default()
+    }
+
+    // Empty board with the terrain, the rules paint it from the tile colors.
+    pub fn board(&self) -> Board {
+        let mut board = Board::new(self.width, self.height);
+        for (pos, terrain) in self.terrain.iter() {
+            board.set_terrain(*pos, *terrain);
+        }
+        board
+    }
+
+    // Every portal with the tile it leads to, portals without exactly one partner lead nowhere.
+    pub fn portals(&self) -> Vec<(Pos, Pos)> {
+        let portals: Vec<(Pos, &String)> = self
+            .special
+            .iter()
+            .filter_map(|(pos, special)| match special {
+                SpecialTile::Portal(pair) => Some((*pos, pair)),
+                _ => None,
+            })
+            .collect();
+        portals
+            .iter()
+            .filter_map(|(pos, pair)| {
+                let mut partners = portals
+                    .iter()
+                    .filter(|(other, other_pair)| other != pos && other_pair == pair);
+                match (partners.next(), partners.next()) {
+                    (Some((partner, _)), None) => Some((*pos, *partner)),
+                    _ => {
+                        println!("Portal {:?} at {:?} needs exactly one partner.", pair, pos);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    // Open tiles enemies of the wave can spawn on, sorted so picking one follows the seed.
+    pub fn spawn_tiles(&self, wave: usize) -> Vec<Pos> {
+        let mut tiles: Vec<Pos> = self
+            .spawn_zones
+            .iter()
+            .filter(|zone| zone.from_wave <= wave)
+            .flat_map(|zone| zone.tiles.iter().copied())
+            .filter(|pos| self.terrain_at(*pos).is_walkable())
+            .filter(|pos| !self.special.iter().any(|(other, _)| other == pos))
+            .collect();
+        tiles.sort_by_key(|pos| (pos.y, pos.x));
+        tiles.dedup();
+        tiles
+    }
+}
+
+// Pools say their color and portals their pair name in properties of their own.
+fn special_tile(properties: &Properties, pos: Pos) -> Option<SpecialTile> {
+    let text = |name: &str| match properties.get(name) {
+        Some(PropertyValue::StringValue(value)) => Some(value.as_str()),
+        _ => None,
+    };
+    let special = match text("special")? {
+        "pool" => match text("color") {
+            Some("red") => SpecialTile::ColorPool(RoundColor::Red),
+            Some("green") => SpecialTile::ColorPool(RoundColor::Green),
+            Some("blue") => SpecialTile::ColorPool(RoundColor::Blue),
+            color => {
+                println!("Color pool at {:?} has no color, got {:?}.", pos, color);
+                return None;
+            }
+        },
+        "portal" => SpecialTile::Portal(text("pair").unwrap_or_default().to_string()),
+        "altar" => SpecialTile::MaskAltar,
+        other => {
+            println!("Unknown special tile {:?} at {:?}.", other, pos);
+            return None;
+        }
+    };
+    Some(special)
+}
+
+#[derive(Resource)]
+pub struct PlayzoneHandle(pub Handle<TiledMapAsset>);
+
+// Runs only start once the map is in, a map that failed to load starts them on the default board.
+#[derive(SystemParam)]
+pub struct PlayzoneMap<'w> {
+    handle: Res<'w, PlayzoneHandle>,
+    maps: Res<'w, Assets<TiledMapAsset>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl PlayzoneMap<'_> {
+    pub fn is_ready(&self) -> bool {
+        self.maps.contains(&self.handle.0)
+            || matches!(
+                self.asset_server.load_state(&self.handle.0),
+                LoadState::Failed(_)
+            )
+    }
+}
+
+pub struct PlayzonePlugin;
+
+impl Plugin for PlayzonePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Playzone>()
+            .add_systems(Startup, load_playzone_map)
+            .add_systems(
+                OnEnter(ApplicationState::Gameplay),
+                read_playzone.before(setup_scene),
+            )
+            .add_systems(Update, log_playzone_reloads);
+    }
+}
+
+fn load_playzone_map(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PlayzoneHandle(asset_server.load(PLAYZONE_PATH)));
+}
+
+// Read once per run, the board doesn't change shape under the units.
+fn read_playzone(
+    handle: Res<PlayzoneHandle>,
+    maps: Res<Assets<TiledMapAsset>>,
+    mut playzone: ResMut<Playzone>,
+) {
+    *playzone = match maps.get(&handle.0) {
+        Some(map) => Playzone::from_map(&map.map),
+        None => {
+            println!("Playzone map failed to load, using the default board.");
+            Playzone::default()
+        }
+    };
+}
+
+fn log_playzone_reloads(
+    mut events: MessageReader<AssetEvent<TiledMapAsset>>,
+    handle: Res<PlayzoneHandle>,
+) {
+    for event in events.read() {
+        if let AssetEvent::Modified { id } = event
+            && *id == handle.0.id()
+        {
+            println!("Playzone reloaded, it applies to the next run.");
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::input::InputSystems;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_ecs_tiled::prelude::*;
+use bevy_egui::input::EguiWantsInput;
+
+use crate::attack_patterns::*;
+use crate::components::*;
+use crate::constants::*;
+use crate::playzone::Playzone;
+use crate::rules::{Abilities, Board, Pos, direction_to, pattern_tiles};
+
+// Playzone tile under the mouse cursor or a finger, none when it's off the board.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HoveredTile(pub Option<TilePos>);
+
+// Sent when a playzone tile is clicked or tapped.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileClicked(pub TilePos);
+
+// One tile of the attack footprint shown while hovering, slot is the attack it belongs to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackPreview {
+    pub slot: usize,
+    pub tile: TilePos,
+}
+
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoveredTile>()
+            .add_message::<TileClicked>()
+            .add_systems(
+                PreUpdate,
+                (
+                    update_pointer_tile,
+                    send_tile_clicks.run_if(in_state(PauseState::Running)),
+                )
+                    .chain()
+                    .after(InputSystems)
+                    .after(bevy::ui::UiSystems::Focus),
+            )
+            .add_systems(
+                Update,
+                update_attack_preview.run_if(in_state(ApplicationState::Gameplay)),
+            );
+    }
+}
+
+// Same math as the troops snapping to the tilemap, only the other way around.
+// Without a window the hovered tile is left alone, the tests set it themselves.
+fn update_pointer_tile(
+    window_q: Query<&Window, With<PrimaryWindow>>,
+    camera_q: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
+    tilemap_q: Query<
+        (
+            &TilemapSize,
+            &TilemapGridSize,
+            &TilemapTileSize,
+            &TilemapType,
+            &TilemapAnchor,
+            &GlobalTransform,
+        ),
+        With<PlayZoneTilemap>,
+    >,
+    touches: Res<Touches>,
+    mut hovered: ResMut<HoveredTile>,
+) {
+    let Ok(window) = window_q.single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_q.single() else {
+        return;
+    };
+    let Ok((map_size, grid_size, tile_size, map_type, anchor, map_transform)) = tilemap_q.single()
+    else {
+        hovered.0 = None;
+        return;
+    };
+
+    // A finger on the screen wins over the cursor, there's no hover without one on touch screens.
+    let screen_pos = touches
+        .first_pressed_position()
+        .or_else(|| window.cursor_position());
+    hovered.0 = screen_pos
+        .and_then(|screen_pos| camera.viewport_to_world_2d(camera_transform, screen_pos).ok())
+        .and_then(|world_pos| {
+            let local = map_transform
+                .affine()
+                .inverse()
+                .transform_point3(world_pos.extend(0.0))
+                .truncate();
+            TilePos::from_world_pos(&local, map_size, grid_size, tile_size, map_type, anchor)
+        });
+}
+
+// A click on a menu button or an egui window is for them, not for the tile under it.
+fn send_tile_clicks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    hovered: Res<HoveredTile>,
+    interaction_q: Query<&Interaction>,
+    egui_input: Option<Res<EguiWantsInput>>,
+    mut clicks: MessageWriter<TileClicked>,
+) {
+    let tapped = touches.iter_just_pressed().next().is_some();
+    if !mouse.just_pressed(MouseButton::Left) && !tapped {
+        return;
+    }
+    let over_button = interaction_q
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    let over_egui = egui_input.is_some_and(|input| input.wants_any_pointer_input());
+    if let Some(tile) = hovered.0
+        && !over_button
+        && !over_egui
+    {
+        clicks.write(TileClicked(tile));
+    }
+}
+
+// Tiles every ready attack paints from the origin, in slot order.
+pub fn attack_previews(
+    patterns: &AttackPatterns,
+    abilities: &Abilities,
+    origin: Pos,
+    board: &Board,
+) -> Vec<(usize, Pos)> {
+    (0..patterns.player.len())
+        .filter_map(|slot| Some((slot, patterns.player_slot(slot)?)))
+        .filter(|(slot, pattern)| abilities.can_use(*slot, pattern))
+        .flat_map(|(slot, pattern)| {
+            pattern_tiles(pattern, origin, board)
+                .into_iter()
+                .map(move |tile| (slot, tile))
+        })
+        .collect()
+}
+
+// While moving, hovering the player or a tile it can step to shows what the attacks reach from there.
+// While attacking, hovering anywhere on the board shows them from the player.
+#[allow(clippy::too_many_arguments)]
+fn update_attack_preview(
+    mut commands: Commands,
+    hovered: Res<HoveredTile>,
+    turn_state: Option<Res<State<TurnState>>>,
+    patterns_handle: Res<AttackPatternsHandle>,
+    patterns: Res<Assets<AttackPatterns>>,
+    abilities: Res<PlayerAbilities>,
+    playzone: Res<Playzone>,
+    player_q: Query<&TilePos, With<Player>>,
+    tilemap_q: Query<
+        (
+            &TilemapSize,
+            &TilemapGridSize,
+            &TilemapTileSize,
+            &TilemapType,
+            &TilemapAnchor,
+        ),
+        With<PlayZoneTilemap>,
+    >,
+    preview_q: Query<(Entity, &AttackPreview)>,
+) {
+    let Ok((map_size, grid_size, tile_size, map_type, anchor)) = tilemap_q.single() else {
+        return;
+    };
+    let player = player_q.single().ok().map(|pos| Pos::new(pos.x, pos.y));
+    let hovered_pos = hovered.0.map(|tile| Pos::new(tile.x, tile.y));
+    let board = playzone.board();
+
+    let origin = match (turn_state.map(|state| *state.get()), player, hovered_pos) {
+        (Some(TurnState::MovePlayer), Some(player), Some(hovered))
+            if hovered == player
+                || (direction_to(player, hovered).is_some() && board.is_walkable(hovered)) =>
+        {
+            Some(hovered)
+        }
+        (Some(TurnState::AttackPlayer), Some(player), Some(_)) => Some(player),
+        _ => None,
+    };
+
+    let previews: Vec<AttackPreview> = origin
+        .zip(patterns.get(&patterns_handle.0))
+        .map(|(origin, patterns)| attack_previews(patterns, &abilities, origin, &board))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(slot, pos)| AttackPreview {
+            slot,
+            tile: TilePos { x: pos.x, y: pos.y },
+        })
+        .collect();
+
+    // Only rebuilt when the footprint changes.
+    let shown: Vec<AttackPreview> = preview_q.iter().map(|(_, preview)| *preview).collect();
+    if shown.len() == previews.len() && previews.iter().all(|preview| shown.contains(preview)) {
+        return;
+    }
+    for (entity, _) in preview_q.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    for preview in previews {
+        let center = preview
+            .tile
+            .center_in_world(map_size, grid_size, tile_size, map_type, anchor);
+        commands.spawn((
+            preview,
+            Sprite {
+                color: ATTACK_PREVIEW_COLORS[preview.slot % ATTACK_PREVIEW_COLORS.len()],
+                custom_size: Some(Vec2::new(tile_size.x, tile_size.y)),
+                ..default()
+            },
+            // Above the tiles, under the troops.
+            Transform::from_xyz(center.x, center.y, LAYER_TILEMAP as f32 + 0.5),
+            DespawnOnExit(ApplicationState::Gameplay),
+        ));
+    }
+}
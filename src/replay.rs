@@ -0,0 +1,200 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::data_files::{data_file, write_file};
+use crate::input::{ActionTaken, InputAction};
+use crate::pointer::TileClicked;
+use crate::save_game::ResumeRun;
+use crate::startup::start_run;
+use crate::update::TurnSystems;
+
+// Speeds the replay panel offers, 1 is the speed the game is played at.
+pub const REPLAY_SPEEDS: [f32; 4] = [0.5, 1.0, 2.0, 4.0];
+
+// A run is its seed and every action that changed it, the rest follows from the rules.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Replay {
+    pub seed: u64,
+    pub actions: Vec<InputAction>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Option<Replay> {
+        let text = fs::read_to_string(path).ok()?;
+        ron::from_str(&text)
+            .inspect_err(|error| {
+                println!("Couldn't read the replay from {}: {}", path.display(), error);
+            })
+            .ok()
+    }
+
+    // One line, the replays are meant to be pasted into bug reports.
+    pub fn save(&self, path: &Path) -> Result<(), BevyError> {
+        write_file(path, &ron::to_string(self)?)
+    }
+}
+
+// Where the last run's replay is kept.
+#[derive(Resource, Debug, Clone)]
+pub struct ReplayPath(pub PathBuf);
+
+impl Default for ReplayPath {
+    fn default() -> Self {
+        ReplayPath(data_file("last_replay.ron"))
+    }
+}
+
+// Replay of the run being played, none for continued runs and playbacks.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ReplayRecorder(pub Option<Replay>);
+
+// Inserted to watch a replay, the run is played with its actions instead of the player's.
+// It stays until the run is left, so the end screen knows not to save a high score.
+#[derive(Resource, Debug, Clone)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    // Index of the next action to play.
+    pub next: usize,
+    pub paused: bool,
+    // Plays on until the next action while paused.
+    pub step: bool,
+    pub speed: f32,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayback {
+            replay,
+            next: 0,
+            paused: false,
+            step: false,
+            speed: 1.0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.actions.len()
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayPath>()
+            .init_resource::<ReplayRecorder>()
+            .add_systems(
+                OnEnter(ApplicationState::Gameplay),
+                (
+                    seed_playback.before(start_run),
+                    start_recording.after(start_run),
+                ),
+            )
+            .add_systems(OnExit(ApplicationState::Gameplay), save_replay)
+            // Leaving the end screen or going back to the menu ends the playback.
+            .add_systems(OnExit(ApplicationState::EndMenu), stop_playback)
+            .add_systems(OnEnter(ApplicationState::MainMenu), stop_playback)
+            .add_systems(
+                Update,
+                (
+                    record_actions.run_if(in_state(ApplicationState::Gameplay)),
+                    (play_back.before(TurnSystems), sync_playback_time)
+                        .run_if(in_state(PauseState::Running))
+                        .run_if(resource_exists::<ReplayPlayback>),
+                ),
+            );
+    }
+}
+
+fn seed_playback(playback: Option<Res<ReplayPlayback>>, mut rng: ResMut<GameRng>) {
+    if let Some(playback) = playback {
+        rng.queue_seed(playback.replay.seed);
+    }
+}
+
+// Continued runs didn't start from their seed, so they can't be replayed.
+fn start_recording(
+    rng: Res<GameRng>,
+    resume: Option<Res<ResumeRun>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    recorder.0 = (resume.is_none() && playback.is_none()).then(|| Replay {
+        seed: rng.seed(),
+        actions: Vec::new(),
+    });
+}
+
+fn record_actions(mut taken: MessageReader<ActionTaken>, mut recorder: ResMut<ReplayRecorder>) {
+    let Some(replay) = recorder.0.as_mut() else {
+        taken.clear();
+        return;
+    };
+    replay.actions.extend(taken.read().map(|taken| taken.0));
+}
+
+fn save_replay(mut recorder: ResMut<ReplayRecorder>, path: Res<ReplayPath>) {
+    let Some(replay) = recorder.0.take() else {
+        return;
+    };
+    if replay.actions.is_empty() {
+        return;
+    }
+    if let Err(error) = replay.save(&path.0) {
+        println!("Couldn't save the replay to {}: {}", path.0.display(), error);
+    }
+}
+
+fn stop_playback(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<ReplayPlayback>();
+    time.set_relative_speed(1.0);
+}
+
+// Gives the next action to the turn when it waits for one, the player's own actions are ignored.
+fn play_back(
+    mut playback: ResMut<ReplayPlayback>,
+    turn_state: Option<Res<State<TurnState>>>,
+    turn_timer: Res<TurnTimer>,
+    mut actions: ResMut<ButtonInput<InputAction>>,
+    mut clicks: ResMut<Messages<TileClicked>>,
+) {
+    for action in InputAction::ALL {
+        // Pause still opens the pause menu.
+        if action != InputAction::Pause {
+            actions.reset(action);
+        }
+    }
+    clicks.clear();
+
+    if playback.paused && !playback.step {
+        return;
+    }
+    let waiting = matches!(
+        turn_state.map(|state| *state.get()),
+        Some(TurnState::MovePlayer | TurnState::AttackPlayer)
+    );
+    if !waiting || !turn_timer.is_finished() {
+        return;
+    }
+    let Some(&action) = playback.replay.actions.get(playback.next) else {
+        return;
+    };
+
+    actions.press(action);
+    playback.next += 1;
+    playback.step = false;
+}
+
+// Paused playback stops the clock like the pause menu does, a step lets it run to the next action.
+fn sync_playback_time(playback: Res<ReplayPlayback>, mut time: ResMut<Time<Virtual>>) {
+    if playback.paused && !playback.step {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+    time.set_relative_speed(playback.speed);
+}
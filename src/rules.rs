@@ -0,0 +1,608 @@
+// The color mask combat rules, written without any Bevy types.
+// The turn systems snapshot the ECS world into a Game, step it with an action and apply the returned events back.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::TILEMAP_SIDE_LENGHT;
+use crate::enemy_ai;
+
+// Kills past this don't earn more charges.
+pub const MAX_CHARGES: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Pos {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl Pos {
+    pub fn new(x: u32, y: u32) -> Self {
+        Pos { x, y }
+    }
+}
+
+// The color that gets masked this round.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RoundColor {
+    #[default]
+    Red,
+    Green,
+    Blue,
+}
+
+impl RoundColor {
+    pub const ALL: [RoundColor; 3] = [RoundColor::Red, RoundColor::Green, RoundColor::Blue];
+
+    // Same indices as RoundColorState: 0 - red, 1 - green, 2 - blue
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            0 => RoundColor::Red,
+            1 => RoundColor::Green,
+            _ => RoundColor::Blue,
+        }
+    }
+
+    pub fn index(self) -> i32 {
+        match self {
+            RoundColor::Red => 0,
+            RoundColor::Green => 1,
+            RoundColor::Blue => 2,
+        }
+    }
+}
+
+// What is painted on a tile.
+// Primary is the masked color and kills enemies, secondary is its complement and kills the player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TilePaint {
+    #[default]
+    Clear,
+    Primary(RoundColor),
+    Secondary(RoundColor),
+}
+
+// What a tile is made of, set with the terrain property in assets/Playzone.tmx.
+// Units only stand on floor, walls also stop attacks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Terrain {
+    #[default]
+    Floor,
+    Wall,
+    Water,
+    Pit,
+}
+
+impl Terrain {
+    pub fn from_name(name: &str) -> Option<Terrain> {
+        match name {
+            "floor" => Some(Terrain::Floor),
+            "wall" => Some(Terrain::Wall),
+            "water" => Some(Terrain::Water),
+            "pit" => Some(Terrain::Pit),
+            _ => None,
+        }
+    }
+
+    pub fn is_walkable(self) -> bool {
+        self == Terrain::Floor
+    }
+
+    pub fn blocks_attacks(self) -> bool {
+        self == Terrain::Wall
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    width: u32,
+    height: u32,
+    tiles: Vec<TilePaint>,
+    terrain: Vec<Terrain>,
+}
+
+impl Board {
+    pub fn new(width: u32, height: u32) -> Self {
+        Board {
+            width,
+            height,
+            tiles: vec![TilePaint::Clear; (width * height) as usize],
+            terrain: vec![Terrain::Floor; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // Safe way to build a position from signed offsets.
+    pub fn pos(&self, x: i32, y: i32) -> Option<Pos> {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            Some(Pos::new(x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> TilePaint {
+        self.index(pos)
+            .map(|index| self.tiles[index])
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, pos: Pos, paint: TilePaint) {
+        if let Some(index) = self.index(pos) {
+            self.tiles[index] = paint;
+        }
+    }
+
+    // Outside the board counts as floor, board.pos already keeps everything on it.
+    pub fn terrain(&self, pos: Pos) -> Terrain {
+        self.index(pos)
+            .map(|index| self.terrain[index])
+            .unwrap_or_default()
+    }
+
+    pub fn set_terrain(&mut self, pos: Pos, terrain: Terrain) {
+        if let Some(index) = self.index(pos) {
+            self.terrain[index] = terrain;
+        }
+    }
+
+    pub fn is_walkable(&self, pos: Pos) -> bool {
+        self.terrain(pos).is_walkable()
+    }
+
+    fn index(&self, pos: Pos) -> Option<usize> {
+        if pos.x < self.width && pos.y < self.height {
+            Some((pos.y * self.width + pos.x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new(TILEMAP_SIDE_LENGHT, TILEMAP_SIDE_LENGHT)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    Player,
+    Enemy,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnitId(pub u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unit {
+    pub id: UnitId,
+    pub side: Side,
+    pub pos: Pos,
+    // Only enemies have a kind.
+    pub kind: Option<EnemyKind>,
+    // Hits it takes before dying.
+    pub health: u32,
+    // Hits are ignored while this is set.
+    pub invulnerable: bool,
+}
+
+// Enemy archetypes, their sprites and stats are in assets/enemy_kinds.ron.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum EnemyKind {
+    #[default]
+    Grunt,
+    Striker,
+    Lancer,
+    Tank,
+    Runner,
+}
+
+// How an enemy kind walks: steps per move, and it only moves on every n-th turn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct EnemyMovement {
+    #[serde(default = "one")]
+    pub steps: u32,
+    #[serde(default = "one")]
+    pub every: u32,
+}
+
+impl Default for EnemyMovement {
+    fn default() -> Self {
+        EnemyMovement { steps: 1, every: 1 }
+    }
+}
+
+fn one() -> u32 {
+    1
+}
+
+// Everything the rules need to know about an enemy kind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnemyRules {
+    pub pattern: AttackPattern,
+    pub movement: EnemyMovement,
+    pub health: u32,
+}
+
+impl Default for EnemyRules {
+    fn default() -> Self {
+        EnemyRules {
+            pattern: AttackPattern::default(),
+            movement: EnemyMovement::default(),
+            health: 1,
+        }
+    }
+}
+
+// The shape of an attack, loaded from assets/attack_patterns.ron.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct AttackPattern {
+    // Directions that repeat up to the range, (1, 0) is a line to the right.
+    #[serde(default)]
+    pub rays: Vec<(i32, i32)>,
+    // Single tiles relative to the unit, the range doesn't apply to them.
+    #[serde(default)]
+    pub offsets: Vec<(i32, i32)>,
+    #[serde(default)]
+    pub range: i32,
+    // Player turns the attack can't be used again after firing it.
+    #[serde(default)]
+    pub cooldown: u32,
+    // Charges the attack uses up, they are earned by killing enemies.
+    #[serde(default)]
+    pub cost: u32,
+}
+
+// Cooldowns and charges of the player's attack slots.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Abilities {
+    cooldowns: Vec<u32>,
+    pub charges: u32,
+}
+
+impl Abilities {
+    // Turns left before the slot can be used again, 0 when it's ready.
+    pub fn cooldown(&self, slot: usize) -> u32 {
+        self.cooldowns.get(slot).copied().unwrap_or(0)
+    }
+
+    pub fn can_use(&self, slot: usize, pattern: &AttackPattern) -> bool {
+        self.cooldown(slot) == 0 && self.charges >= pattern.cost
+    }
+
+    // Every attack is a player turn, so the other cooldowns tick down before the used slot starts its own.
+    pub fn use_slot(&mut self, slot: usize, pattern: &AttackPattern) {
+        for cooldown in self.cooldowns.iter_mut() {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+        if self.cooldowns.len() <= slot {
+            self.cooldowns.resize(slot + 1, 0);
+        }
+        self.cooldowns[slot] = pattern.cooldown;
+        self.charges -= pattern.cost;
+    }
+
+    pub fn add_kills(&mut self, kills: u32) {
+        self.charges = (self.charges + kills).min(MAX_CHARGES);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    PickColor(RoundColor),
+    SpawnEnemy(Pos, EnemyKind),
+    MovePlayer(MoveDirection),
+    PlayerAttack(AttackPattern),
+    MoveEnemies,
+    EnemiesAttack,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    ColorPicked(RoundColor),
+    EnemySpawned { unit: UnitId, pos: Pos, kind: EnemyKind },
+    UnitMoved { unit: UnitId, from: Pos, to: Pos },
+    TilesPainted { tiles: Vec<Pos>, paint: TilePaint },
+    UnitDamaged { unit: UnitId, health: u32 },
+    UnitKilled { unit: UnitId, pos: Pos },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Game {
+    pub board: Board,
+    pub units: Vec<Unit>,
+    pub round_color: RoundColor,
+    // Stats of every enemy kind, kinds missing here don't move or attack.
+    pub roster: BTreeMap<EnemyKind, EnemyRules>,
+    // Turns played this run, slow enemies only move on some of them.
+    pub turn: u32,
+    next_id: u32,
+}
+
+impl Game {
+    pub fn new(board: Board, round_color: RoundColor) -> Self {
+        Game {
+            board,
+            units: Vec::new(),
+            round_color,
+            roster: BTreeMap::new(),
+            turn: 0,
+            next_id: 0,
+        }
+    }
+
+    // Ids are handed out in order, starting at 0.
+    pub fn add_unit(&mut self, side: Side, pos: Pos) -> UnitId {
+        let id = UnitId(self.next_id);
+        self.next_id += 1;
+        self.units.push(Unit {
+            id,
+            side,
+            pos,
+            kind: None,
+            health: 1,
+            invulnerable: false,
+        });
+        id
+    }
+
+    pub fn add_enemy(&mut self, kind: EnemyKind, pos: Pos, health: u32) -> UnitId {
+        let id = self.add_unit(Side::Enemy, pos);
+        if let Some(unit) = self.units.iter_mut().find(|unit| unit.id == id) {
+            unit.kind = Some(kind);
+            unit.health = health;
+        }
+        id
+    }
+
+    pub fn enemy_rules(&self, unit: &Unit) -> Option<&EnemyRules> {
+        unit.kind.and_then(|kind| self.roster.get(&kind))
+    }
+
+    pub fn unit(&self, id: UnitId) -> Option<&Unit> {
+        self.units.iter().find(|unit| unit.id == id)
+    }
+
+    pub fn player(&self) -> Option<&Unit> {
+        self.units.iter().find(|unit| unit.side == Side::Player)
+    }
+
+    pub fn enemies(&self) -> impl Iterator<Item = &Unit> {
+        self.units.iter().filter(|unit| unit.side == Side::Enemy)
+    }
+
+    pub fn is_occupied(&self, pos: Pos) -> bool {
+        self.units.iter().any(|unit| unit.pos == pos)
+    }
+
+    // Whether the unit may end up on the tile, enemies never go onto the color they die on.
+    pub fn can_enter(&self, unit: UnitId, pos: Pos) -> bool {
+        let Some(unit) = self.unit(unit) else {
+            return false;
+        };
+        let lethal =
+            unit.side == Side::Enemy && self.board.get(pos) == TilePaint::Primary(self.round_color);
+        self.board.pos(pos.x as i32, pos.y as i32).is_some()
+            && self.board.is_walkable(pos)
+            && !self
+                .units
+                .iter()
+                .any(|other| other.id != unit.id && other.pos == pos)
+            && !lethal
+    }
+
+    // Every kind goes on the next tile that is still free, the caller shuffles the tiles.
+    // Kinds that find no free tile aren't spawned, only the events of the ones that were come back.
+    pub fn spawn_enemies(&mut self, kinds: &[EnemyKind], tiles: &[Pos]) -> Vec<Event> {
+        let mut tiles = tiles.iter().copied();
+        let mut events = Vec::new();
+        for &kind in kinds {
+            let Some(spawned) = tiles
+                .by_ref()
+                .map(|pos| self.step(Action::SpawnEnemy(pos, kind)))
+                .find(|spawned| !spawned.is_empty())
+            else {
+                break;
+            };
+            events.extend(spawned);
+        }
+        events
+    }
+
+    // Tiles the enemies paint if they attacked from where they stand now.
+    pub fn enemy_attack_tiles(&self) -> Vec<Pos> {
+        self.enemies()
+            .filter_map(|enemy| {
+                let rules = self.enemy_rules(enemy)?;
+                Some(pattern_tiles(&rules.pattern, enemy.pos, &self.board))
+            })
+            .flatten()
+            .collect()
+    }
+
+    pub fn step(&mut self, action: Action) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        match action {
+            Action::PickColor(color) => {
+                self.round_color = color;
+                events.push(Event::ColorPicked(color));
+            }
+            Action::SpawnEnemy(pos, kind) => {
+                if self.board.pos(pos.x as i32, pos.y as i32).is_some()
+                    && self.board.is_walkable(pos)
+                    && !self.is_occupied(pos)
+                {
+                    let health = self.roster.get(&kind).map_or(1, |rules| rules.health);
+                    let unit = self.add_enemy(kind, pos, health);
+                    events.push(Event::EnemySpawned { unit, pos, kind });
+                }
+            }
+            Action::MovePlayer(direction) => {
+                if let Some(player) = self.player().copied() {
+                    let from = player.pos;
+                    // Moving against the edge, into a wall or into an enemy keeps the player in place.
+                    let to = step_in_direction(&self.board, from, direction)
+                        .filter(|to| self.can_enter(player.id, *to))
+                        .unwrap_or(from);
+                    if to != from
+                        && let Some(player) =
+                            self.units.iter_mut().find(|unit| unit.id == player.id)
+                    {
+                        player.pos = to;
+                        events.push(Event::UnitMoved {
+                            unit: player.id,
+                            from,
+                            to,
+                        });
+                    }
+                }
+            }
+            Action::PlayerAttack(pattern) => {
+                if let Some(player) = self.player().copied() {
+                    let paint = TilePaint::Primary(self.round_color);
+                    let tiles = pattern_tiles(&pattern, player.pos, &self.board);
+                    self.paint(tiles, paint, &mut events);
+                    self.hit_on_paint(Side::Enemy, paint, &mut events);
+                }
+            }
+            Action::MoveEnemies => {
+                // One at a time, so every enemy sees where the previous ones ended up.
+                let enemies: Vec<(UnitId, EnemyRules)> = self
+                    .enemies()
+                    .filter_map(|enemy| Some((enemy.id, self.enemy_rules(enemy)?.clone())))
+                    .collect();
+                for (unit, rules) in enemies {
+                    if !self.turn.is_multiple_of(rules.movement.every.max(1)) {
+                        continue;
+                    }
+                    for _ in 0..rules.movement.steps {
+                        let Some(to) = enemy_ai::next_step(self, unit, &rules.pattern) else {
+                            break;
+                        };
+                        if let Some(enemy) = self.units.iter_mut().find(|enemy| enemy.id == unit) {
+                            let from = enemy.pos;
+                            enemy.pos = to;
+                            events.push(Event::UnitMoved { unit, from, to });
+                        }
+                    }
+                }
+            }
+            Action::EnemiesAttack => {
+                let paint = TilePaint::Secondary(self.round_color);
+                let tiles = self.enemy_attack_tiles();
+                self.paint(tiles, paint, &mut events);
+                self.hit_on_paint(Side::Player, paint, &mut events);
+            }
+        }
+
+        events
+    }
+
+    fn paint(&mut self, tiles: Vec<Pos>, paint: TilePaint, events: &mut Vec<Event>) {
+        if tiles.is_empty() {
+            return;
+        }
+        for tile in tiles.iter() {
+            self.board.set(*tile, paint);
+        }
+        events.push(Event::TilesPainted { tiles, paint });
+    }
+
+    // Units standing on a tile painted in their lethal color lose a hit, the last one kills them.
+    fn hit_on_paint(&mut self, side: Side, paint: TilePaint, events: &mut Vec<Event>) {
+        let board = &self.board;
+        self.units.retain_mut(|unit| {
+            if unit.side != side || unit.invulnerable || board.get(unit.pos) != paint {
+                return true;
+            }
+            unit.health = unit.health.saturating_sub(1);
+            if unit.health > 0 {
+                events.push(Event::UnitDamaged {
+                    unit: unit.id,
+                    health: unit.health,
+                });
+                return true;
+            }
+            events.push(Event::UnitKilled {
+                unit: unit.id,
+                pos: unit.pos,
+            });
+            false
+        });
+    }
+}
+
+// The tile next to the unit, none off the board or where it can't stand.
+pub fn step_in_direction(board: &Board, pos: Pos, direction: MoveDirection) -> Option<Pos> {
+    let (dx, dy) = match direction {
+        MoveDirection::Up => (0, 1),
+        MoveDirection::Down => (0, -1),
+        MoveDirection::Left => (-1, 0),
+        MoveDirection::Right => (1, 0),
+    };
+    board
+        .pos(pos.x as i32 + dx, pos.y as i32 + dy)
+        .filter(|next| board.is_walkable(*next))
+}
+
+// Direction of a tile right next to the unit, none for any other tile.
+pub fn direction_to(from: Pos, to: Pos) -> Option<MoveDirection> {
+    let dx = to.x as i64 - from.x as i64;
+    let dy = to.y as i64 - from.y as i64;
+    match (dx, dy) {
+        (0, 1) => Some(MoveDirection::Up),
+        (0, -1) => Some(MoveDirection::Down),
+        (-1, 0) => Some(MoveDirection::Left),
+        (1, 0) => Some(MoveDirection::Right),
+        _ => None,
+    }
+}
+
+// This goes around the unit tile and gets the neighbours the pattern reaches.
+pub fn pattern_tiles(pattern: &AttackPattern, pos: Pos, board: &Board) -> Vec<Pos> {
+    // This is where the unit is.
+    // From a gameplay perspective looks better on the map.
+    let mut tiles = vec![pos];
+    // Walls are never painted, a ray stops at the first one.
+    let mut push = |x: i32, y: i32| {
+        let tile = board
+            .pos(pos.x as i32 + x, pos.y as i32 + y)
+            .filter(|tile| !board.terrain(*tile).blocks_attacks())?;
+        if !tiles.contains(&tile) {
+            tiles.push(tile);
+        }
+        Some(tile)
+    };
+
+    for (dx, dy) in pattern.offsets.iter() {
+        push(*dx, *dy);
+    }
+    let mut blocked = vec![false; pattern.rays.len()];
+    for offset in 1..=pattern.range {
+        for ((dx, dy), blocked) in pattern.rays.iter().zip(blocked.iter_mut()) {
+            if !*blocked && push(dx * offset, dy * offset).is_none() {
+                *blocked = true;
+            }
+        }
+    }
+    tiles
+}
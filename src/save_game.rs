@@ -0,0 +1,404 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::constants::*;
+use crate::data_files::{data_file, save_ron};
+use crate::enemy_kinds::*;
+use crate::replay::ReplayPlayback;
+use crate::rules::{Abilities, EnemyKind, Pos, TilePaint};
+use crate::special_tiles::MaskAltar;
+use crate::stats::RunStats;
+use crate::troop_utilities::*;
+use crate::undo::UndosLeft;
+use crate::update::spawn_spectrum;
+use crate::waves::WaveDirector;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SavedUnit {
+    pub pos: Pos,
+    // Only enemies have a kind.
+    pub kind: Option<EnemyKind>,
+    pub health: u32,
+    pub invulnerable_until: Option<u32>,
+}
+
+// Everything needed to continue a run where it was left.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SaveGame {
+    // Saved when a phase that waits on a timer or the player is entered, it's entered again on continue.
+    pub phase: TurnState,
+    pub round_color: i32,
+    pub turn: u32,
+    pub seed: u64,
+    pub rng_word_pos: u64,
+    pub player: SavedUnit,
+    pub enemies: Vec<SavedUnit>,
+    // Only painted tiles, the rest are clear.
+    pub tiles: Vec<(Pos, TilePaint)>,
+    pub abilities: Abilities,
+    pub waves: WaveDirector,
+    pub stats: RunStats,
+    // Mask altars the player already used.
+    #[serde(default)]
+    pub altars_used: Vec<Pos>,
+    #[serde(default = "all_undos")]
+    pub undos_left: u32,
+    // Moves left in the player's move, it can be saved halfway through by quitting.
+    #[serde(default = "one_move")]
+    pub action_points: u32,
+}
+
+// Saves from before the undos were saved get the whole budget.
+fn all_undos() -> u32 {
+    UNDOS_PER_RUN
+}
+
+fn one_move() -> u32 {
+    1
+}
+
+impl SaveGame {
+    // A broken save is reported and ignored, the player can still start a new run.
+    pub fn load(path: &Path) -> Option<SaveGame> {
+        let text = fs::read_to_string(path).ok()?;
+        ron::from_str(&text)
+            .inspect_err(|error| {
+                println!("Couldn't read the save from {}: {}", path.display(), error);
+            })
+            .ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BevyError> {
+        save_ron(path, self)
+    }
+
+    pub fn delete(path: &Path) -> Result<(), BevyError> {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Where the run is saved.
+#[derive(Resource, Debug, Clone)]
+pub struct SavePath(pub PathBuf);
+
+impl Default for SavePath {
+    fn default() -> Self {
+        SavePath(data_file("save.ron"))
+    }
+}
+
+impl SavePath {
+    pub fn exists(&self) -> bool {
+        self.0.is_file()
+    }
+}
+
+// Inserted by Continue, the run is restored from it instead of starting at the first turn.
+#[derive(Resource, Debug, Clone)]
+pub struct ResumeRun(pub SaveGame);
+
+type EnemyData = (
+    &'static TilePos,
+    Option<&'static Kind>,
+    Option<&'static Health>,
+    Option<&'static Invulnerable>,
+);
+
+// Every part of the world that goes into a save.
+#[derive(SystemParam)]
+pub struct RunState<'w, 's> {
+    commands: Commands<'w, 's>,
+    asset_server: Res<'w, AssetServer>,
+    texture_atlas_layouts: ResMut<'w, Assets<TextureAtlasLayout>>,
+    enemy_kinds_handle: Res<'w, EnemyKindsHandle>,
+    enemy_kinds: Res<'w, Assets<EnemyKinds>>,
+    turn_state: Option<Res<'w, State<TurnState>>>,
+    rng: ResMut<'w, GameRng>,
+    turn_count: ResMut<'w, TurnCount>,
+    abilities: ResMut<'w, PlayerAbilities>,
+    director: ResMut<'w, WaveDirector>,
+    stats: ResMut<'w, RunStats>,
+    undos: ResMut<'w, UndosLeft>,
+    action_points: ResMut<'w, ActionPoints>,
+    color_state_q: Query<'w, 's, &'static mut RoundColorState>,
+    tilemap_q: Query<'w, 's, (&'static TileStorage, &'static TilemapSize), With<PlayZoneTilemap>>,
+    tile_q: Query<'w, 's, &'static mut TileColor>,
+    player_q: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut TilePos,
+            &'static mut Health,
+            Option<&'static Invulnerable>,
+        ),
+        With<Player>,
+    >,
+    enemy_q: Query<'w, 's, EnemyData, (With<Enemy>, Without<Player>)>,
+    enemy_entity_q: Query<'w, 's, Entity, With<Enemy>>,
+    spectrum_q: Query<'w, 's, Entity, With<SpectrumElement>>,
+    altar_q: Query<'w, 's, (&'static TilePos, &'static mut MaskAltar), Without<Player>>,
+}
+
+impl RunState<'_, '_> {
+    // None when there is no run to save, like after the player died.
+    pub fn capture(&self) -> Option<SaveGame> {
+        let phase = *self.turn_state.as_ref()?.get();
+        let color_state = self.color_state_q.single().ok()?;
+        let (_, player_pos, player_health, player_invulnerable) = self.player_q.single().ok()?;
+
+        let enemies = self
+            .enemy_q
+            .iter()
+            .map(|(pos, kind, health, invulnerable)| SavedUnit {
+                pos: Pos::new(pos.x, pos.y),
+                kind: Some(kind.map_or(EnemyKind::default(), |kind| kind.0)),
+                health: health.map_or(1, |health| health.0),
+                invulnerable_until: invulnerable.map(|invulnerable| invulnerable.until_turn),
+            })
+            .collect();
+
+        let (storage, map_size) = self.tilemap_q.single().ok()?;
+        let mut tiles = Vec::new();
+        for x in 0..map_size.x {
+            for y in 0..map_size.y {
+                let paint = storage
+                    .get(&TilePos { x, y })
+                    .and_then(|tile_entity| self.tile_q.get(tile_entity).ok())
+                    .map(|tile_color| paint_from_color(tile_color.0))
+                    .unwrap_or_default();
+                if paint != TilePaint::Clear {
+                    tiles.push((Pos::new(x, y), paint));
+                }
+            }
+        }
+
+        Some(SaveGame {
+            phase,
+            round_color: color_state.index,
+            turn: self.turn_count.0,
+            seed: self.rng.seed(),
+            rng_word_pos: self.rng.word_pos(),
+            player: SavedUnit {
+                pos: Pos::new(player_pos.x, player_pos.y),
+                kind: None,
+                health: player_health.0,
+                invulnerable_until: player_invulnerable.map(|invulnerable| invulnerable.until_turn),
+            },
+            enemies,
+            tiles,
+            abilities: self.abilities.0.clone(),
+            waves: self.director.clone(),
+            stats: self.stats.clone(),
+            altars_used: self
+                .altar_q
+                .iter()
+                .filter(|(_, altar)| altar.used)
+                .map(|(pos, _)| Pos::new(pos.x, pos.y))
+                .collect(),
+            undos_left: self.undos.0,
+            action_points: self.action_points.0,
+        })
+    }
+
+    pub fn undos_left(&self) -> u32 {
+        self.undos.0
+    }
+
+    // Runs on a freshly set up scene, so only what differs from a new run is changed.
+    pub fn restore(&mut self, save: &SaveGame) {
+        self.rng.resume(save.seed, save.rng_word_pos);
+        self.turn_count.0 = save.turn;
+        self.abilities.0 = save.abilities.clone();
+        *self.director = save.waves.clone();
+        *self.stats = save.stats.clone();
+        self.undos.0 = save.undos_left;
+        self.action_points.0 = save.action_points;
+
+        for mut color_state in self.color_state_q.iter_mut() {
+            color_state.index = save.round_color;
+        }
+        spawn_spectrum(&mut self.commands, &self.asset_server, save.round_color);
+        for (pos, mut altar) in self.altar_q.iter_mut() {
+            altar.used = save.altars_used.contains(&Pos::new(pos.x, pos.y));
+        }
+
+        if let Ok((storage, _)) = self.tilemap_q.single() {
+            for (pos, paint) in &save.tiles {
+                if let Some(tile_entity) = storage.get(&TilePos { x: pos.x, y: pos.y })
+                    && let Ok(mut tile_color) = self.tile_q.get_mut(tile_entity)
+                {
+                    *tile_color = TileColor(paint_color(*paint));
+                }
+            }
+        }
+
+        if let Ok((entity, mut tile_pos, mut health, _)) = self.player_q.single_mut() {
+            *tile_pos = TilePos {
+                x: save.player.pos.x,
+                y: save.player.pos.y,
+            };
+            health.0 = save.player.health;
+            insert_invulnerable(&mut self.commands, entity, save.player.invulnerable_until);
+        }
+
+        let Some(enemy_kinds) = self.enemy_kinds.get(&self.enemy_kinds_handle.0) else {
+            println!("Enemy kinds not loaded.");
+            return;
+        };
+        for enemy in &save.enemies {
+            let kind = enemy.kind.unwrap_or_default();
+            let Some(archetype) = enemy_kinds.get(kind) else {
+                continue;
+            };
+            let entity = spawn_enemy(
+                enemy.pos.x,
+                enemy.pos.y,
+                kind,
+                archetype,
+                &mut self.commands,
+                &self.asset_server,
+                &mut self.texture_atlas_layouts,
+            );
+            self.commands.entity(entity).insert(Health(enemy.health));
+            insert_invulnerable(&mut self.commands, entity, enemy.invulnerable_until);
+        }
+    }
+
+    // Puts a run that is still going back to the save, for undo.
+    // Everything restore expects from a fresh scene is cleared out first.
+    // The undo budget isn't rewound, one undo is spent on it instead.
+    pub fn rewind(&mut self, save: &SaveGame) {
+        for entity in self.enemy_entity_q.iter().chain(self.spectrum_q.iter()) {
+            self.commands.entity(entity).despawn();
+        }
+        if let Ok((entity, ..)) = self.player_q.single() {
+            self.commands
+                .entity(entity)
+                .remove::<Invulnerable>()
+                .insert(Visibility::Inherited);
+        }
+        if let Ok((storage, map_size)) = self.tilemap_q.single() {
+            for x in 0..map_size.x {
+                for y in 0..map_size.y {
+                    if let Some(tile_entity) = storage.get(&TilePos { x, y })
+                        && let Ok(mut tile_color) = self.tile_q.get_mut(tile_entity)
+                    {
+                        *tile_color = TileColor(paint_color(TilePaint::Clear));
+                    }
+                }
+            }
+        }
+        let undos_left = self.undos.0.saturating_sub(1);
+        self.restore(save);
+        self.undos.0 = undos_left;
+    }
+}
+
+fn insert_invulnerable(commands: &mut Commands, entity: Entity, until_turn: Option<u32>) {
+    if let Some(until_turn) = until_turn {
+        commands.entity(entity).insert(Invulnerable { until_turn });
+    }
+}
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<SavePath>() {
+            app.init_resource::<SavePath>();
+        }
+
+        app.add_systems(
+            OnEnter(TurnState::ColorPick),
+            (resume_run, recolor_units)
+                .chain()
+                .run_if(resource_exists::<ResumeRun>),
+        )
+        // Only the phases that wait are saved, nothing changes while they wait
+        // and entering them again doesn't redo any of the turn.
+        .add_systems(OnEnter(TurnState::MovePlayer), autosave)
+        .add_systems(OnEnter(TurnState::AttackPlayer), autosave)
+        .add_systems(OnEnter(TurnState::MoveEnemy), autosave)
+        .add_systems(OnEnter(EnemyAttackPhase::Windup), autosave)
+        // A finished run can't be continued.
+        .add_systems(OnEnter(ApplicationState::EndMenu), delete_save.run_if(not_watching));
+    }
+}
+
+fn not_watching(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_none()
+}
+
+// Saves the run as it is right now, for the autosaves and for quitting.
+#[derive(SystemParam)]
+pub struct RunSaver<'w, 's> {
+    run: RunState<'w, 's>,
+    path: Res<'w, SavePath>,
+    playback: Option<Res<'w, ReplayPlayback>>,
+}
+
+impl RunSaver<'_, '_> {
+    pub fn save(&self) {
+        // A replay being watched is never saved over the player's run.
+        if self.playback.is_some() {
+            return;
+        }
+        let Some(save) = self.run.capture() else {
+            return;
+        };
+        if let Err(error) = save.save(&self.path.0) {
+            println!(
+                "Couldn't save the run to {}: {}",
+                self.path.0.display(),
+                error
+            );
+        }
+    }
+}
+
+fn resume_run(
+    mut commands: Commands,
+    resume: Res<ResumeRun>,
+    mut run: RunState,
+    mut next_state: ResMut<NextState<TurnState>>,
+) {
+    run.restore(&resume.0);
+    next_state.set(resume.0.phase);
+    commands.remove_resource::<ResumeRun>();
+    println!("Continuing the run at turn {}.", resume.0.turn);
+}
+
+// The restored units are spawned by then, they get the colors of the saved round.
+pub fn recolor_units(
+    player_q: Query<&mut Sprite, With<Player>>,
+    enemy_q: Query<&mut Sprite, (With<Enemy>, Without<Player>)>,
+    colorstate: Query<&mut RoundColorState>,
+) {
+    update_player_color(player_q, &colorstate);
+    update_enemy_color(enemy_q, &colorstate);
+}
+
+fn autosave(saver: RunSaver) {
+    saver.save();
+}
+
+fn delete_save(path: Res<SavePath>) {
+    if let Err(error) = SaveGame::delete(&path.0) {
+        println!(
+            "Couldn't delete the save at {}: {}",
+            path.0.display(),
+            error
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use rand::Rng;
+
+use crate::components::*;
+use crate::playzone::{Playzone, SpecialTile};
+use crate::rules::{Pos, RoundColor};
+use crate::stats::RunStats;
+use crate::troop_utilities::*;
+use crate::update::{TurnSystems, spawn_spectrum};
+
+// Kills by color pools go into the run stats under this name, next to the attack pattern ids.
+pub const POOL_KILLS: &str = "pool";
+
+// Kills the enemies stepping in while its color is the masked one.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorPool(pub RoundColor);
+
+// Sends units stepping on it to the paired portal.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Portal(pub TilePos);
+
+// The player stepping on it re-rolls the masked color, only the first time.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaskAltar {
+    pub used: bool,
+}
+
+// Written when a unit walks onto a tile, not when it's spawned, restored or teleported there.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitEnteredTile {
+    pub entity: Entity,
+    pub tile: TilePos,
+}
+
+pub struct SpecialTilesPlugin;
+
+impl Plugin for SpecialTilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<UnitEnteredTile>().add_systems(
+            Update,
+            (enter_pools_and_portals, enter_mask_altars)
+                .chain()
+                .after(TurnSystems)
+                .run_if(in_state(ApplicationState::Gameplay)),
+        );
+    }
+}
+
+// Called while the playzone tiles are spawned, before the storage goes on the tilemap.
+pub fn insert_special_tiles(commands: &mut Commands, storage: &TileStorage, playzone: &Playzone) {
+    let portals = playzone.portals();
+    for (pos, special) in playzone.special.iter() {
+        let Some(tile_entity) = storage.get(&TilePos { x: pos.x, y: pos.y }) else {
+            continue;
+        };
+        let mut tile = commands.entity(tile_entity);
+        match special {
+            SpecialTile::ColorPool(color) => {
+                tile.insert(ColorPool(*color));
+            }
+            SpecialTile::Portal(_) => {
+                if let Some((_, to)) = portals.iter().find(|(from, _)| from == pos) {
+                    tile.insert(Portal(TilePos { x: to.x, y: to.y }));
+                }
+            }
+            SpecialTile::MaskAltar => {
+                tile.insert(MaskAltar::default());
+            }
+        }
+    }
+}
+
+// Units moving more than one tile only trigger the one they end up on.
+fn last_entered(entered: &mut MessageReader<UnitEnteredTile>) -> Vec<UnitEnteredTile> {
+    let mut last: Vec<UnitEnteredTile> = Vec::new();
+    for message in entered.read() {
+        last.retain(|other| other.entity != message.entity);
+        last.push(*message);
+    }
+    last
+}
+
+#[allow(clippy::too_many_arguments)]
+fn enter_pools_and_portals(
+    mut entered: MessageReader<UnitEnteredTile>,
+    tilemap_q: Query<&TileStorage, With<PlayZoneTilemap>>,
+    pool_q: Query<&ColorPool>,
+    portal_q: Query<&Portal>,
+    enemy_q: Query<(), With<Enemy>>,
+    mut abilities: ResMut<PlayerAbilities>,
+    mut stats: ResMut<RunStats>,
+    mut rules: RulesAdapter,
+) {
+    let entered = last_entered(&mut entered);
+    let Ok(storage) = tilemap_q.single() else {
+        return;
+    };
+
+    let mut drowned = 0;
+    for UnitEnteredTile { entity, tile } in entered {
+        let Some(tile_entity) = storage.get(&tile) else {
+            continue;
+        };
+        // Enemies wear the masked color, the player its complement, so only enemies drown.
+        if let Ok(pool) = pool_q.get(tile_entity)
+            && enemy_q.contains(entity)
+            && rules.round_color() == Some(pool.0)
+        {
+            rules.kill(entity);
+            drowned += 1;
+            continue;
+        }
+        // An exit the unit couldn't walk onto keeps it where it is.
+        if let Ok(portal) = portal_q.get(tile_entity) {
+            rules.teleport(entity, Pos::new(portal.0.x, portal.0.y));
+        }
+    }
+
+    // Drowned enemies count like the ones the player's attacks kill.
+    if drowned > 0 {
+        abilities.add_kills(drowned);
+        stats.record_kills(POOL_KILLS, drowned);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn enter_mask_altars(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut entered: MessageReader<UnitEnteredTile>,
+    tilemap_q: Query<&TileStorage, With<PlayZoneTilemap>>,
+    mut altar_q: Query<&mut MaskAltar>,
+    mut rng: ResMut<GameRng>,
+    mut stats: ResMut<RunStats>,
+    mut color_state_q: Query<&mut RoundColorState>,
+    spectrum_q: Query<Entity, With<SpectrumElement>>,
+    player_q: Query<&mut Sprite, With<Player>>,
+    enemy_q: Query<&mut Sprite, (With<Enemy>, Without<Player>)>,
+) {
+    let entered = last_entered(&mut entered);
+    let Ok(storage) = tilemap_q.single() else {
+        return;
+    };
+    let Some(tile_entity) = entered
+        .iter()
+        .filter(|entered| player_q.contains(entered.entity))
+        .filter_map(|entered| storage.get(&entered.tile))
+        .find(|tile_entity| altar_q.contains(*tile_entity))
+    else {
+        return;
+    };
+    let Ok(mut altar) = altar_q.get_mut(tile_entity) else {
+        return;
+    };
+    if altar.used {
+        return;
+    }
+    altar.used = true;
+
+    for entity in spectrum_q.iter() {
+        commands.entity(entity).despawn();
+    }
+    for mut color_state in color_state_q.iter_mut() {
+        // Always another color, a re-roll to the same one would waste the altar.
+        color_state.index = (color_state.index + rng.random_range(1..3)) % 3;
+        stats.record_color(RoundColor::from_index(color_state.index));
+        spawn_spectrum(&mut commands, &asset_server, color_state.index);
+    }
+    update_player_color(player_q, &color_state_q);
+    update_enemy_color(enemy_q, &color_state_q);
+}
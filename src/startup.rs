@@ -1,44 +1,48 @@
 use crate::components::*;
 use crate::constants::*;
+use crate::playzone::{Playzone, PlayzoneHandle};
+use crate::special_tiles::insert_special_tiles;
 use bevy::prelude::Handle;
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::*;
 
-use crate::post_processing;
+// Runs before the scene is built so every random decision of the run comes from its seed.
+pub fn start_run(mut rng: ResMut<GameRng>) {
+    rng.start_run();
+}
 
 pub fn setup_scene(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    playzone: Res<Playzone>,
+    playzone_handle: Res<PlayzoneHandle>,
 ) {
-    // Spawn Camera
-    commands.spawn((
-        Camera2d, // Add the setting to the camera.
-        // This component is also used to determine on which camera to run the post processing effect.
-        post_processing::PostProcessSettings {
-            intensity: 25.2,
-            ..default()
-        },
-    ));
-
     // Spawn tilemap
     // Load a map asset and retrieve its handle
     let map_handle: Handle<TiledMapAsset> = asset_server.load("Trees.tmx");
 
     // Spawn a new entity with the TiledMap component
     let background_tilemap = commands.spawn(TiledMap(map_handle)).id();
-    commands
-        .entity(background_tilemap)
-        .insert(Transform::from_xyz(-328., -232., LAYER_TILEMAP as f32));
+    commands.entity(background_tilemap).insert((
+        Transform::from_xyz(-328., -232., LAYER_TILEMAP as f32),
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
 
-    // Spawn Application State, this controls the state of the application
-    commands.spawn((RoundColorState::default(), RoundColorState2));
+    // The terrain art of the playzone map, drawn over the colored tiles.
+    commands.spawn((
+        TiledMap(playzone_handle.0.clone()),
+        TilemapAnchor::Center,
+        Transform::from_xyz(0., 0., LAYER_TILEMAP as f32 + 0.2),
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
 
     // Spawn Application State, this controls the state of the application
-    commands.spawn(GlobalApplicationState::default());
-
-    // Spawn Turn State, this controls the turn based mechanics of the game.
-    commands.spawn(GlobalTurnState::default());
+    commands.spawn((
+        RoundColorState::default(),
+        RoundColorState2,
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
 
     let texture = asset_server.load("Player.png");
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 3, 1, None, None);
@@ -49,7 +53,11 @@ pub fn setup_scene(
     // Spawn Player: troop, player, sprite and transform components.
     commands.spawn((
         Player,
-        TilePos { x: 8, y: 3 },
+        Health(PLAYER_LIVES),
+        TilePos {
+            x: playzone.player_start.x,
+            y: playzone.player_start.y,
+        },
         Transform::from_xyz(0., 0., LAYER_PLAYER as f32),
         Sprite::from_atlas_image(
             texture,
@@ -60,6 +68,7 @@ pub fn setup_scene(
         ),
         animation_indices,
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+        DespawnOnExit(ApplicationState::Gameplay),
     ));
 
     // Playable zone tilemap
@@ -67,8 +76,8 @@ pub fn setup_scene(
 
     // Size of the tile map in tiles.
     let map_size = TilemapSize {
-        x: TILEMAP_SIDE_LENGHT,
-        y: TILEMAP_SIDE_LENGHT,
+        x: playzone.width,
+        y: playzone.height,
     };
 
     // To create a map we use the TileStorage component.
@@ -87,11 +96,14 @@ pub fn setup_scene(
         for y in 0..map_size.y {
             let tile_pos = TilePos { x, y };
             let tile_entity = commands
-                .spawn(TileBundle {
-                    position: tile_pos,
-                    tilemap_id: TilemapId(tilemap_entity),
-                    ..Default::default()
-                })
+                .spawn((
+                    TileBundle {
+                        position: tile_pos,
+                        tilemap_id: TilemapId(tilemap_entity),
+                        ..Default::default()
+                    },
+                    DespawnOnExit(ApplicationState::Gameplay),
+                ))
                 .id();
 
             // Here I set the tile storage to component know what tiles we have like the graphical content.
@@ -102,6 +114,9 @@ pub fn setup_scene(
         }
     }
 
+    // Pools, portals and altars go on the tiles they are drawn on.
+    insert_special_tiles(&mut commands, &tile_storage, &playzone);
+
     // This is the size of each individual tiles in pixels.
     let tile_size = TilemapTileSize {
         x: TILEMAP_SIDE_LENGHT as f32,
@@ -122,6 +137,7 @@ pub fn setup_scene(
             ..Default::default()
         },
         PlayZoneTilemap,
+        DespawnOnExit(ApplicationState::Gameplay),
     ));
 
     commands
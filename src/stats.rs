@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::rules::RoundColor;
+
+pub const SCORE_PER_KILL: u32 = 100;
+pub const SCORE_PER_TURN: u32 = 10;
+
+// Everything worth remembering about the current run, kept after it ends for the end screen.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStats {
+    pub score: u32,
+    // Kills by attack pattern id.
+    pub kills: BTreeMap<String, u32>,
+    // Kills of the last player attack and the most in a single one.
+    pub combo: u32,
+    pub best_combo: u32,
+    pub turns_survived: u32,
+    // Rounds every color was the masked one, indexed like RoundColor::index.
+    pub colors_seen: [u32; 3],
+}
+
+impl RunStats {
+    pub fn record_attack(&mut self, pattern: &str, kills: u32) {
+        self.combo = kills;
+        self.best_combo = self.best_combo.max(kills);
+        self.record_kills(pattern, kills);
+    }
+
+    // Every kill at the same time is worth more than the one before.
+    // Kills outside of an attack, like drowning in a pool, don't touch the combo.
+    pub fn record_kills(&mut self, source: &str, kills: u32) {
+        if kills > 0 {
+            *self.kills.entry(source.to_string()).or_default() += kills;
+        }
+        self.score += SCORE_PER_KILL * kills * (kills + 1) / 2;
+    }
+
+    pub fn record_turn_survived(&mut self) {
+        self.turns_survived += 1;
+        self.score += SCORE_PER_TURN;
+    }
+
+    pub fn record_color(&mut self, color: RoundColor) {
+        self.colors_seen[color.index() as usize] += 1;
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    // Short summary for the end screen.
+    pub fn summary(&self) -> Vec<String> {
+        let kills = if self.kills.is_empty() {
+            String::from("none")
+        } else {
+            self.kills
+                .iter()
+                .map(|(pattern, kills)| format!("{} {}", pattern, kills))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let [red, green, blue] = self.colors_seen;
+
+        vec![
+            format!("Score: {}", self.score),
+            format!("Turns survived: {}", self.turns_survived),
+            format!("Kills: {} ({})", self.total_kills(), kills),
+            format!("Best combo: {}", self.best_combo),
+            format!("Colors seen: red {}, green {}, blue {}", red, green, blue),
+        ]
+    }
+}
+
+// Marks the Text2d that shows the stats while playing.
+#[derive(Component)]
+pub struct StatsText;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(OnEnter(ApplicationState::Gameplay), reset_stats)
+            .add_systems(
+                Update,
+                render_stats_text.run_if(in_state(ApplicationState::Gameplay)),
+            );
+    }
+}
+
+fn reset_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn render_stats_text(stats: Res<RunStats>, mut query: Query<&mut Text2d, With<StatsText>>) {
+    if !stats.is_changed() {
+        return;
+    }
+
+    let combo = if stats.combo > 1 {
+        format!("  Combo x{}", stats.combo)
+    } else {
+        String::new()
+    };
+    for mut text in &mut query {
+        text.0 = format!(
+            "Score {}  Kills {}  Turns {}{}",
+            stats.score,
+            stats.total_kills(),
+            stats.turns_survived,
+            combo
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use crate::components::*;
+use crate::constants::*;
+use crate::health::DamageTaken;
+use crate::rules::Pos;
+use crate::troop_utilities::RulesSnapshot;
+use crate::update::TurnSystems;
+
+// Tiles the enemies will hit at the end of this turn, empty once they attacked.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq)]
+pub struct EnemyIntents(pub Vec<Pos>);
+
+// Overlay on one tile of the enemy intents.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntentMarker(pub TilePos);
+
+pub struct TelegraphPlugin;
+
+impl Plugin for TelegraphPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnemyIntents>()
+            .add_systems(OnEnter(TurnState::MovePlayer), update_enemy_intents)
+            .add_systems(OnEnter(EnemyAttackPhase::Windup), update_enemy_intents)
+            .add_systems(OnEnter(EnemyAttackPhase::Cooldown), update_enemy_intents)
+            .add_systems(
+                Update,
+                (
+                    update_enemy_intents
+                        .run_if(
+                            in_state(TurnState::MovePlayer).or(in_state(TurnState::AttackPlayer)),
+                        )
+                        .run_if(board_changed.or(on_message::<DamageTaken>)),
+                    update_intent_markers.run_if(resource_changed::<EnemyIntents>),
+                    pulse_intent_markers,
+                )
+                    .chain()
+                    .after(TurnSystems)
+                    .run_if(in_state(ApplicationState::Gameplay)),
+            );
+    }
+}
+
+// From the start of the player turn until the windup is over, the intents follow the enemies around.
+// Until the enemies moved they show where they will hit after their move, not where they stand.
+// Worked out when the player turn and the windup start, and again when the player moves, paints or
+// kills. Never while the enemies move, their new tiles would show a second move for a frame.
+fn update_enemy_intents(
+    turn_state: Option<Res<State<TurnState>>>,
+    attack_phase: Option<Res<State<EnemyAttackPhase>>>,
+    rules: RulesSnapshot,
+    mut intents: ResMut<EnemyIntents>,
+) {
+    let tiles = match turn_state.map(|state| *state.get()) {
+        Some(TurnState::MovePlayer | TurnState::AttackPlayer | TurnState::MoveEnemy) => {
+            rules.enemy_intents(false)
+        }
+        Some(TurnState::AttackEnemy)
+            if attack_phase.is_some_and(|phase| *phase.get() == EnemyAttackPhase::Windup) =>
+        {
+            rules.enemy_intents(true)
+        }
+        _ => Vec::new(),
+    };
+    intents.set_if_neq(EnemyIntents(tiles));
+}
+
+// Units moved or spawned, or tiles got painted.
+fn board_changed(
+    unit_q: Query<(), (Changed<TilePos>, Or<(With<Player>, With<Enemy>)>)>,
+    tile_q: Query<(), Changed<TileColor>>,
+) -> bool {
+    !unit_q.is_empty() || !tile_q.is_empty()
+}
+
+fn update_intent_markers(
+    mut commands: Commands,
+    intents: Res<EnemyIntents>,
+    marker_q: Query<Entity, With<IntentMarker>>,
+    tilemap_q: Query<
+        (
+            &TilemapSize,
+            &TilemapGridSize,
+            &TilemapTileSize,
+            &TilemapType,
+            &TilemapAnchor,
+        ),
+        With<PlayZoneTilemap>,
+    >,
+) {
+    for entity in marker_q.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Ok((map_size, grid_size, tile_size, map_type, anchor)) = tilemap_q.single() else {
+        return;
+    };
+
+    for pos in intents.0.iter() {
+        let tile_pos = TilePos { x: pos.x, y: pos.y };
+        let center = tile_pos.center_in_world(map_size, grid_size, tile_size, map_type, anchor);
+        commands.spawn((
+            IntentMarker(tile_pos),
+            Sprite {
+                color: INTENT_COLOR.with_alpha(INTENT_ALPHA.0),
+                custom_size: Some(Vec2::new(tile_size.x, tile_size.y)),
+                ..default()
+            },
+            // Above the attack preview, under the troops.
+            Transform::from_xyz(center.x, center.y, LAYER_TILEMAP as f32 + 0.6),
+            DespawnOnExit(ApplicationState::Gameplay),
+        ));
+    }
+}
+
+fn pulse_intent_markers(time: Res<Time>, mut marker_q: Query<&mut Sprite, With<IntentMarker>>) {
+    let wave = (time.elapsed_secs() * std::f32::consts::TAU / INTENT_PULSE_SECONDS).sin();
+    let alpha = INTENT_ALPHA.0 + (INTENT_ALPHA.1 - INTENT_ALPHA.0) * (wave + 1.0) / 2.0;
+    for mut sprite in marker_q.iter_mut() {
+        sprite.color.set_alpha(alpha);
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::math::ops;
 use bevy::prelude::*;
 
-use crate::components::{GlobalTurnState, RoundColorState, TurnState};
+use crate::components::{ApplicationState, RoundColorState, TurnState};
+use crate::stats::StatsText;
 
 #[derive(Component)]
 pub struct AnimateTranslation;
@@ -28,6 +29,22 @@ pub fn setup_font(mut commands: Commands, asset_server: Res<AssetServer>) {
         TextColor(Color::WHITE),
         Transform::from_translation(Vec3::new(0.0, -120.0, 4.0)),
         TextBackgroundColor(Color::BLACK.with_alpha(0.0)),
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
+
+    // Live run stats, right under the status.
+    commands.spawn((
+        StatsText,
+        Text2d::new(""),
+        TextFont {
+            font,
+            font_size: 12.0,
+            ..default()
+        },
+        TextLayout::new_with_justify(text_justification).with_no_wrap(),
+        TextColor(Color::BLACK),
+        Transform::from_translation(Vec3::new(0.0, -136.0, 4.0)),
+        DespawnOnExit(ApplicationState::Gameplay),
     ));
 }
 
@@ -43,15 +60,15 @@ pub fn render_translated_text(
 
 // Credit: ChatGPT Codex cause I needed to convert the state of the game fast
 pub fn render_rotated_text(
-    mut query: Query<(&mut Transform, &mut Text2d, &mut TextColor), With<Text2d>>,
+    mut query: Query<(&mut Transform, &mut Text2d, &mut TextColor), Without<StatsText>>,
     color_state: Query<&RoundColorState>,
-    turn_state: Query<&GlobalTurnState>,
+    turn_state: Option<Res<State<TurnState>>>,
 ) {
     
     let color = Color::BLACK;
 
-    let status_label = match turn_state.single() {
-        Ok(state) => match state.turn_state {
+    let status_label = match turn_state {
+        Some(state) => match state.get() {
             TurnState::ColorPick => "Color Pick",
             TurnState::PlayerChange => "Player Change",
             TurnState::EnemySpawn => "Enemy Spawn",
@@ -60,7 +77,7 @@ pub fn render_rotated_text(
             TurnState::MoveEnemy => "Move Enemy",
             TurnState::AttackEnemy => "Enemy Attacks",
         },
-        Err(_) => "Status",
+        None => "Status",
     };
 
     let text_value = status_label.to_string();
@@ -1,38 +1,59 @@
+use crate::attack_patterns::*;
 use crate::components::*;
 use crate::constants::*;
+use crate::enemy_kinds::*;
+use crate::health::DamageTaken;
+use crate::playzone::Playzone;
+use crate::special_tiles::UnitEnteredTile;
+use crate::rules::{Action, EnemyKind, Event, Game, Pos, RoundColor, Side, TilePaint, UnitId};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::*;
+use rand::Rng;
+use rand::seq::SliceRandom;
 
 pub fn spawn_enemy(
     tile_pos_x: u32,
     tile_pos_y: u32,
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-) {
-    let texture = asset_server.load("Enemy.png");
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 3, 1, None, None);
+    kind: EnemyKind,
+    archetype: &EnemyArchetype,
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) -> Entity {
+    let texture = asset_server.load(&archetype.sprite);
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), archetype.frames, 1, None, None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let animation_indices = AnimationIndices { first: 0, last: 2 };
+    let animation_indices = AnimationIndices {
+        first: archetype.first,
+        last: archetype.last,
+    };
+
+    let mut sprite = Sprite::from_atlas_image(
+        texture,
+        TextureAtlas {
+            layout: texture_atlas_layout,
+            index: animation_indices.first,
+        },
+    );
+    sprite.custom_size = Some(Vec2::splat(archetype.size));
 
     commands.spawn((
         Troop,
         Enemy,
+        Kind(kind),
+        Health(archetype.health.max(1)),
         TilePos {
             x: tile_pos_x,
             y: tile_pos_y,
         },
         Transform::from_xyz(0., 0., LAYER_PLAYER as f32),
-        Sprite::from_atlas_image(
-            texture,
-            TextureAtlas {
-                layout: texture_atlas_layout,
-                index: animation_indices.first,
-            },
-        ),
+        sprite,
         animation_indices,
         AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
-    ));
+        DespawnOnExit(ApplicationState::Gameplay),
+    ))
+    .id()
 }
 
 // Credit: snapping idea to center in world from Codex 5.2
@@ -98,15 +119,6 @@ pub fn update_enemy_troop_to_tilemap(
     }
 }
 
-#[derive(Clone, Copy)]
-// Avoid long if checks inside the coloring functions for player, q is diagonal, etc
-pub enum AttackPattern {
-    Diagonal,
-    Sides,
-    Around,
-    Ultimate,
-}
-
 fn masked_color_from_index(index: i32) -> Color {
     match index {
         0 => Color::srgba(1.0, 0.0, 0.0, 1.0),
@@ -125,252 +137,331 @@ fn player_color_from_index(index: i32) -> Color {
     }
 }
 
-// Helper function to safely create a tile position with bounds checking
-fn try_tile_pos(x: i32, y: i32, map_size: &TilemapSize) -> Option<TilePos> {
-    if x >= 0 && y >= 0 && (x as u32) < map_size.x && (y as u32) < map_size.y {
-        Some(TilePos {
-            x: x as u32,
-            y: y as u32,
-        })
-    } else {
-        None
+// Tiles show the masked color when painted by the player and the player color when painted by enemies.
+pub fn paint_color(paint: TilePaint) -> Color {
+    match paint {
+        TilePaint::Clear => Color::WHITE,
+        TilePaint::Primary(color) => masked_color_from_index(color.index()),
+        TilePaint::Secondary(color) => player_color_from_index(color.index()),
     }
 }
 
-// This goes around the player tile and gets the neighbours
-// Spits out vector used when calling color player/enemy neighbour
-fn gather_neighbors(
-    pattern: AttackPattern,
-    pos: TilePos,
-    map_size: &TilemapSize,
-    attack_lenght: i32
-) -> Vec<TilePos> {
-    let mut neighbors = Vec::new();
-    let px = pos.x as i32;
-    let py = pos.y as i32;
-
-    // This is where the unit is.
-    // From a gameplay perspective looks better on the map.
-    if let Some(tile) = try_tile_pos(px, py, map_size) {
-        neighbors.push(tile);
+pub fn paint_from_color(color: Color) -> TilePaint {
+    for round_color in RoundColor::ALL {
+        if color == masked_color_from_index(round_color.index()) {
+            return TilePaint::Primary(round_color);
+        }
+        if color == player_color_from_index(round_color.index()) {
+            return TilePaint::Secondary(round_color);
+        }
     }
+    TilePaint::Clear
+}
 
-    match pattern {
-        AttackPattern::Diagonal => {
-            for offset in 1..=attack_lenght {
-                if let Some(tile) = try_tile_pos(px + offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px + offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-            }
-        }
-        AttackPattern::Sides => {
-            for offset in 1..=attack_lenght {
-                if let Some(tile) = try_tile_pos(px + offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
+// What the rules read from the world besides the units and the tile colors.
+#[derive(SystemParam)]
+pub struct RulesContext<'w, 's> {
+    patterns_handle: Res<'w, AttackPatternsHandle>,
+    patterns: Res<'w, Assets<AttackPatterns>>,
+    enemy_kinds_handle: Res<'w, EnemyKindsHandle>,
+    enemy_kinds: Res<'w, Assets<EnemyKinds>>,
+    turn_count: Res<'w, TurnCount>,
+    playzone: Res<'w, Playzone>,
+    color_state_q: Query<'w, 's, &'static RoundColorState>,
+    tilemap_q: Query<'w, 's, (&'static TileStorage, &'static TilemapSize), With<PlayZoneTilemap>>,
+}
+
+// A unit as the snapshot sees it, the same for the read-only and the mutable unit queries.
+type UnitItem<'a> = (
+    Entity,
+    &'a TilePos,
+    bool,
+    Option<&'a Kind>,
+    Option<&'a Health>,
+    Option<&'a Invulnerable>,
+);
+
+impl RulesContext<'_, '_> {
+    // The unit ids of the snapshot index into the returned entities.
+    fn snapshot<'a>(
+        &self,
+        tile_color: impl Fn(Entity) -> Option<&'a TileColor>,
+        units: impl Iterator<Item = UnitItem<'a>>,
+    ) -> Option<(Game, Vec<Entity>)> {
+        let Ok((storage, map_size)) = self.tilemap_q.single() else {
+            println!("No tilemap.");
+            return None;
+        };
+        let color_state = self.color_state_q.single().ok()?;
+
+        let mut board = self.playzone.board();
+        for x in 0..map_size.x {
+            for y in 0..map_size.y {
+                let paint = storage
+                    .get(&TilePos { x, y })
+                    .and_then(&tile_color)
+                    .map(|tile_color| paint_from_color(tile_color.0))
+                    .unwrap_or_default();
+                board.set(Pos::new(x, y), paint);
             }
         }
-        AttackPattern::Around => {
-            for offset in 1..=attack_lenght {
-                if let Some(tile) = try_tile_pos(px + offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px + offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px + offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-            }
+
+        let mut game = Game::new(board, RoundColor::from_index(color_state.index));
+        game.turn = self.turn_count.0;
+        if let Some(enemy_kinds) = self.enemy_kinds.get(&self.enemy_kinds_handle.0)
+            && let Some(patterns) = self.patterns.get(&self.patterns_handle.0)
+        {
+            game.roster = enemy_kinds.roster(patterns);
+        } else {
+            println!("Enemy kinds not loaded.");
         }
-        AttackPattern::Ultimate => {
-            for offset in 1..=attack_lenght {
-                if let Some(tile) = try_tile_pos(px + offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px + offset, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px + offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px - offset, py, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py + offset, map_size) {
-                    neighbors.push(tile);
-                }
-                if let Some(tile) = try_tile_pos(px, py - offset, map_size) {
-                    neighbors.push(tile);
-                }
+
+        let mut entities = Vec::new();
+        for (entity, tile_pos, is_player, kind, health, invulnerable) in units {
+            let pos = Pos::new(tile_pos.x, tile_pos.y);
+            let health = health.map_or(1, |health| health.0);
+            let unit = if is_player {
+                game.add_unit(Side::Player, pos)
+            } else {
+                let kind = kind.map_or(EnemyKind::default(), |kind| kind.0);
+                game.add_enemy(kind, pos, health)
+            };
+            if let Some(unit) = game.units.iter_mut().find(|other| other.id == unit) {
+                unit.health = health;
+                unit.invulnerable =
+                    invulnerable.is_some_and(|invulnerable| invulnerable.until_turn >= game.turn);
             }
+            entities.push(entity);
         }
+
+        Some((game, entities))
     }
+}
 
-    neighbors
+// Read-only view of the rules, for systems that only look ahead and never change the world.
+#[derive(SystemParam)]
+pub struct RulesSnapshot<'w, 's> {
+    context: RulesContext<'w, 's>,
+    tile_q: Query<'w, 's, &'static TileColor>,
+    unit_q: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static TilePos,
+            Has<Player>,
+            Option<&'static Kind>,
+            Option<&'static Health>,
+            Option<&'static Invulnerable>,
+        ),
+        Or<(With<Player>, With<Enemy>)>,
+    >,
 }
 
-fn apply_tile_color(
-    neighbors: Vec<TilePos>,
-    storage: &TileStorage,
-    tile_q: &mut Query<&mut TileColor>,
-    color: Color,
-) {
-    for tile in neighbors {
-        if let Some(tile_entity) = storage.get(&tile) {
-            if let Ok(mut tile_color) = tile_q.get_mut(tile_entity) {
-                *tile_color = TileColor(color);
+impl RulesSnapshot<'_, '_> {
+    pub fn snapshot(&self) -> Option<(Game, Vec<Entity>)> {
+        self.context.snapshot(
+            |tile_entity| self.tile_q.get(tile_entity).ok(),
+            self.unit_q.iter(),
+        )
+    }
+
+    // Every tile an enemy attack reaches once the enemies made their move, once each.
+    // Enemies that already moved this turn attack from where they stand.
+    pub fn enemy_intents(&self, enemies_moved: bool) -> Vec<Pos> {
+        let Some((mut game, _)) = self.snapshot() else {
+            return Vec::new();
+        };
+        if !enemies_moved {
+            game.step(Action::MoveEnemies);
+        }
+        let mut tiles = Vec::new();
+        for tile in game.enemy_attack_tiles() {
+            if !tiles.contains(&tile) {
+                tiles.push(tile);
             }
         }
+        tiles
     }
 }
 
-// Retrieving the neighboring tiles and coloring them
-pub fn color_player_neighbors(
-    pattern: AttackPattern,
-    player_q: &mut Query<(Entity, &mut TilePos), (With<Player>, Without<Enemy>)>, // We want the player
-    tilemap_q: Query<(&TileStorage, &TilemapSize), With<PlayZoneTilemap>>, // Retrieve the tilemap and it's bundaries for safe writing
-    tile_q: &mut Query<&mut TileColor>,
-    color_state: &Query<&mut RoundColorState>,
-) {
-    let Ok((storage, map_size)) = tilemap_q.single() else {
-        println!("No tilemap.");
-        return;
-    };
-    let Ok(color_state) = color_state.single() else {
-        return;
-    };
-    let attack_color = masked_color_from_index(color_state.index);
+// Thin layer between the ECS world and the rules in rules.rs.
+// Every step takes a snapshot of the playzone, runs the rules and writes the events back.
+#[derive(SystemParam)]
+pub struct RulesAdapter<'w, 's> {
+    context: RulesContext<'w, 's>,
+    commands: Commands<'w, 's>,
+    asset_server: Res<'w, AssetServer>,
+    texture_atlas_layouts: ResMut<'w, Assets<TextureAtlasLayout>>,
+    damage_taken: MessageWriter<'w, DamageTaken>,
+    entered: MessageWriter<'w, UnitEnteredTile>,
+    tile_q: Query<'w, 's, &'static mut TileColor>,
+    unit_q: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static mut TilePos,
+            Has<Player>,
+            Option<&'static Kind>,
+            Option<&'static mut Health>,
+            Option<&'static Invulnerable>,
+        ),
+        Or<(With<Player>, With<Enemy>)>,
+    >,
+}
 
-    for (_, pos) in player_q.iter_mut() {
-        let pos = TilePos { x: pos.x, y: pos.y };
-        let neighbors = gather_neighbors(pattern, pos, map_size, 5);
-        apply_tile_color(neighbors, storage, tile_q, attack_color);
+impl RulesAdapter<'_, '_> {
+    pub fn snapshot(&self) -> Option<(Game, Vec<Entity>)> {
+        self.context.snapshot(
+            |tile_entity| self.tile_q.get(tile_entity).ok(),
+            self.unit_q.iter(),
+        )
     }
-}
 
-// Credit: Codex 5.2, inspired by my intial player coloring
-pub fn color_enemy_neighbors(
-    enemy_q: &mut Query<(Entity, &mut TilePos), (With<Enemy>, Without<Player>)>,
-    tilemap_q: Query<(&TileStorage, &TilemapSize), With<PlayZoneTilemap>>,
-    tile_q: &mut Query<&mut TileColor>,
-    color_state: &Query<&mut RoundColorState>,
-) {
-    let Ok((storage, map_size)) = tilemap_q.single() else {
-        println!("No tilemap.");
-        return;
-    };
-    let Ok(color_state) = color_state.single() else {
-        return;
-    };
-    let attack_color = player_color_from_index(color_state.index);
+    pub fn player_pos(&self) -> Option<Pos> {
+        self.unit_q
+            .iter()
+            .find(|(_, _, is_player, ..)| *is_player)
+            .map(|(_, tile_pos, ..)| Pos::new(tile_pos.x, tile_pos.y))
+    }
 
-    for (_, pos) in enemy_q.iter_mut() {
-        let pos = TilePos { x: pos.x, y: pos.y };
-        let neighbors = gather_neighbors(AttackPattern::Sides, pos, map_size, 2);
-        apply_tile_color(neighbors, storage, tile_q, attack_color);
+    // Kind picked by the spawn weights of the enemy kinds asset.
+    pub fn pick_enemy_kind(&self, rng: &mut impl Rng) -> Option<EnemyKind> {
+        self.context
+            .enemy_kinds
+            .get(&self.context.enemy_kinds_handle.0)
+            .and_then(|enemy_kinds| enemy_kinds.pick(rng))
     }
-}
 
-// AI-generated (Codex): helper for kill checks based on tile color.
-fn tile_matches_color(
-    storage: &TileStorage,
-    tile_q: &mut Query<&mut TileColor>,
-    pos: &TilePos,
-    target: Color,
-) -> bool {
-    if let Some(tile_entity) = storage.get(pos) {
-        if let Ok(tile_color) = tile_q.get_mut(tile_entity) {
-            return tile_color.0 == target;
-        }
+    // Puts the kinds on the free spawn tiles of the wave in a random order, returns how many fit.
+    pub fn spawn_enemies(&mut self, kinds: &[EnemyKind], wave: usize, rng: &mut impl Rng) -> u32 {
+        let mut tiles = self.context.playzone.spawn_tiles(wave);
+        tiles.shuffle(rng);
+        // All spawns of the turn go on one snapshot so they see each other's tiles.
+        let events = self.step_with(|game| game.spawn_enemies(kinds, &tiles));
+        events
+            .iter()
+            .filter(|event| matches!(event, Event::EnemySpawned { .. }))
+            .count() as u32
     }
-    false
-}
 
-// AI-generated (Codex): despawn enemies standing on tiles matching their color.
-pub fn despawn_enemies_on_matching_tile_color(
-    mut commands: Commands,
-    enemy_q: &mut Query<(Entity, &mut TilePos), (With<Enemy>, Without<Player>)>,
-    tilemap_q: Query<(&TileStorage, &TilemapSize), With<PlayZoneTilemap>>,
-    tile_q: &mut Query<&mut TileColor>,
-    color_state: &Query<&mut RoundColorState>,
-) {
-    let Ok((storage, _map_size)) = tilemap_q.single() else {
-        return;
-    };
-    let Ok(color_state) = color_state.single() else {
-        return;
-    };
-    let enemy_color = masked_color_from_index(color_state.index);
+    pub fn step(&mut self, action: Action) -> Vec<Event> {
+        self.step_with(|game| game.step(action))
+    }
+
+    // Several steps on one snapshot, for actions that have to see each other.
+    // Spawns only show up in the world once the commands run, a new snapshot would miss them.
+    pub fn step_with(&mut self, play: impl FnOnce(&mut Game) -> Vec<Event>) -> Vec<Event> {
+        let Some((mut game, entities)) = self.snapshot() else {
+            return Vec::new();
+        };
+        let events = play(&mut game);
+        self.apply(&events, &entities);
+        events
+    }
+
+    fn apply(&mut self, events: &[Event], entities: &[Entity]) {
+        let Ok((storage, _map_size)) = self.context.tilemap_q.single() else {
+            return;
+        };
 
-    for (entity, pos) in enemy_q.iter_mut() {
-        let pos = TilePos { x: pos.x, y: pos.y };
-        if tile_matches_color(storage, tile_q, &pos, enemy_color) {
-            commands.entity(entity).despawn();
+        for event in events {
+            match event {
+                Event::ColorPicked(_) => {}
+                Event::EnemySpawned { pos, kind, .. } => {
+                    let Some(archetype) = self
+                        .context
+                        .enemy_kinds
+                        .get(&self.context.enemy_kinds_handle.0)
+                        .and_then(|enemy_kinds| enemy_kinds.get(*kind))
+                    else {
+                        println!("Enemy kinds not loaded.");
+                        continue;
+                    };
+                    spawn_enemy(
+                        pos.x,
+                        pos.y,
+                        *kind,
+                        archetype,
+                        &mut self.commands,
+                        &self.asset_server,
+                        &mut self.texture_atlas_layouts,
+                    );
+                }
+                Event::UnitMoved { unit, to, .. } => {
+                    if let Some(&entity) = entities.get(unit.0 as usize)
+                        && let Ok((_, mut tile_pos, ..)) = self.unit_q.get_mut(entity)
+                    {
+                        *tile_pos = TilePos { x: to.x, y: to.y };
+                        self.entered.write(UnitEnteredTile {
+                            entity,
+                            tile: *tile_pos,
+                        });
+                    }
+                }
+                Event::UnitDamaged { unit, health } => {
+                    if let Some(&entity) = entities.get(unit.0 as usize)
+                        && let Ok((.., Some(mut unit_health), _)) = self.unit_q.get_mut(entity)
+                    {
+                        unit_health.0 = *health;
+                        self.damage_taken.write(DamageTaken {
+                            entity,
+                            health: *health,
+                        });
+                    }
+                }
+                Event::TilesPainted { tiles, paint } => {
+                    for tile in tiles {
+                        if let Some(tile_entity) = storage.get(&TilePos {
+                            x: tile.x,
+                            y: tile.y,
+                        }) && let Ok(mut tile_color) = self.tile_q.get_mut(tile_entity)
+                        {
+                            *tile_color = TileColor(paint_color(*paint));
+                        }
+                    }
+                }
+                Event::UnitKilled { unit, .. } => {
+                    if let Some(&entity) = entities.get(unit.0 as usize) {
+                        self.kill(entity);
+                    }
+                }
+            }
         }
     }
-}
 
-// AI-generated (Codex): despawn the player if standing on a tile matching the player color.
-pub fn despawn_player_on_matching_tile_color(
-    mut commands: Commands,
-    player_q: &mut Query<(Entity, &mut TilePos), (With<Player>, Without<Enemy>)>,
-    tilemap_q: Query<(&TileStorage, &TilemapSize), With<PlayZoneTilemap>>,
-    tile_q: &mut Query<&mut TileColor>,
-    color_state: &Query<&mut RoundColorState>,
-) {
-    let Ok((storage, _map_size)) = tilemap_q.single() else {
-        return;
-    };
-    let Ok(color_state) = color_state.single() else {
-        return;
-    };
-    let player_color = player_color_from_index(color_state.index);
+    pub fn kill(&mut self, entity: Entity) {
+        self.damage_taken.write(DamageTaken { entity, health: 0 });
+        // Out of the game right away, the death animation takes care of the despawn.
+        self.commands
+            .entity(entity)
+            .remove::<(Player, Enemy)>()
+            .insert(Dying(Timer::from_seconds(DEATH_ANIMATION_SECONDS, TimerMode::Once)));
+    }
 
-    for (entity, pos) in player_q.iter_mut() {
-        let pos = TilePos { x: pos.x, y: pos.y };
-        if tile_matches_color(storage, tile_q, &pos, player_color) {
-            commands.entity(entity).despawn();
+    // Puts the unit straight on the tile, false when it couldn't walk there either.
+    // It doesn't count as entering the tile, so portals don't send it back.
+    pub fn teleport(&mut self, entity: Entity, to: Pos) -> bool {
+        let Some((game, entities)) = self.snapshot() else {
+            return false;
+        };
+        let Some(unit) = entities.iter().position(|other| *other == entity) else {
+            return false;
+        };
+        if !game.can_enter(UnitId(unit as u32), to) {
+            return false;
         }
+        let Ok((_, mut tile_pos, ..)) = self.unit_q.get_mut(entity) else {
+            return false;
+        };
+        *tile_pos = TilePos { x: to.x, y: to.y };
+        true
+    }
+
+    pub fn round_color(&self) -> Option<RoundColor> {
+        let color_state = self.context.color_state_q.single().ok()?;
+        Some(RoundColor::from_index(color_state.index))
     }
 }
 
@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::constants::*;
+use crate::input::{ActionTaken, InputAction};
+use crate::save_game::*;
+use crate::update::TurnSystems;
+
+// Snapshots taken when the player's move starts, the last one is the turn being played.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct TurnHistory(pub Vec<SaveGame>);
+
+// Undos still left in this run, reset when a run starts.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+pub struct UndosLeft(pub u32);
+
+impl Default for UndosLeft {
+    fn default() -> Self {
+        UndosLeft(UNDOS_PER_RUN)
+    }
+}
+
+// Sent when the run was rewound to the start of a player turn.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnRewound {
+    pub turn: u32,
+}
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TurnHistory>()
+            .init_resource::<UndosLeft>()
+            .add_message::<TurnRewound>()
+            .add_systems(OnEnter(ApplicationState::Gameplay), clear_history)
+            .add_systems(OnEnter(TurnState::MovePlayer), record_turn)
+            .add_systems(
+                Update,
+                (undo_turn, recolor_units.run_if(on_message::<TurnRewound>))
+                    .chain()
+                    .in_set(TurnSystems)
+                    .run_if(
+                        in_state(TurnState::MovePlayer)
+                            .or(in_state(TurnState::AttackPlayer))
+                            .and(in_state(PauseState::Running)),
+                    ),
+            );
+    }
+}
+
+fn clear_history(mut history: ResMut<TurnHistory>) {
+    history.0.clear();
+}
+
+// Rewinding enters the move again, the snapshot it came from isn't recorded twice.
+fn record_turn(run: RunState, mut history: ResMut<TurnHistory>) {
+    let Some(save) = run.capture() else {
+        return;
+    };
+    if history.0.last() == Some(&save) {
+        return;
+    }
+    history.0.push(save);
+    let extra = history.0.len().saturating_sub(UNDO_TURNS + 1);
+    history.0.drain(..extra);
+}
+
+// Goes back to the start of this turn, or to the one before when nothing was done yet.
+fn undo_turn(
+    actions: Res<ButtonInput<InputAction>>,
+    mut run: RunState,
+    mut history: ResMut<TurnHistory>,
+    mut next_state: ResMut<NextState<TurnState>>,
+    mut rewound: MessageWriter<TurnRewound>,
+    mut taken: MessageWriter<ActionTaken>,
+) {
+    if !actions.just_pressed(InputAction::Undo) {
+        return;
+    }
+    if run.undos_left() == 0 {
+        println!("No undos left this run.");
+        return;
+    }
+    let current = run.capture();
+    if history.0.len() > 1 && history.0.last() == current.as_ref() {
+        history.0.pop();
+    }
+    // Rewinding to where the run already is would only waste an undo.
+    let Some(save) = history
+        .0
+        .last()
+        .filter(|save| Some(*save) != current.as_ref())
+        .cloned()
+    else {
+        println!("Nothing to undo.");
+        return;
+    };
+
+    run.rewind(&save);
+    // The snapshots only differ from the run by the spent undo now, they still count as the same turn.
+    for snapshot in history.0.iter_mut() {
+        snapshot.undos_left = run.undos_left();
+    }
+    next_state.set(save.phase);
+    rewound.write(TurnRewound { turn: save.turn });
+    taken.write(ActionTaken(InputAction::Undo));
+    println!(
+        "Rewound to turn {}, {} undos left.",
+        save.turn,
+        run.undos_left()
+    );
+}
@@ -1,348 +1,343 @@
+use crate::attack_patterns::*;
+use crate::characters::*;
 use crate::components::*;
 use crate::constants::*;
+use crate::enemy_kinds::*;
+use crate::input::{ActionTaken, InputAction};
+use crate::pointer::TileClicked;
+use crate::waves::*;
+use crate::stats::RunStats;
+use crate::rules::{Action, EnemyKind, Event, MoveDirection, Pos, RoundColor, direction_to};
+use crate::save_game::ResumeRun;
 use crate::troop_utilities::*;
+use crate::undo::UndosLeft;
 
 use bevy::prelude::*;
-use bevy_ecs_tiled::prelude::*;
-use rand::Rng;
-
-#[derive(Default)]
-pub(crate) struct StateEntryDelay {
-    last_state: Option<TurnState>,
-    // One-shot timer used to pause only once when a state is entered.
-    timer: Option<Timer>,
+
+// The systems that play the turn phases, anything that feeds them actions runs before.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TurnSystems;
+
+// Every phase of a turn is its own system, the state machine decides which one runs.
+pub struct TurnPlugin;
+
+impl Plugin for TurnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<TurnState>()
+            .add_sub_state::<EnemyAttackPhase>()
+            .init_resource::<TurnTimer>()
+            .init_resource::<PlayerAbilities>()
+            .init_resource::<ActionPoints>()
+            .init_resource::<TurnCount>()
+            .init_resource::<RunOutcome>()
+            // Every run starts at the first turn with everything off cooldown, no charges and every undo.
+            // A continued run keeps the undos it had left, see save_game.rs.
+            .add_systems(OnEnter(ApplicationState::Gameplay), reset_run)
+            // Instant phases, they do their work once and move on.
+            // A continued run restores its saved turn instead, see save_game.rs.
+            .add_systems(
+                OnEnter(TurnState::ColorPick),
+                (count_turn, color_pick_update).run_if(not(resource_exists::<ResumeRun>)),
+            )
+            .add_systems(OnEnter(TurnState::PlayerChange), player_change_update)
+            // The move is refilled before it's entered, a continued or rewound move keeps what it had.
+            .add_systems(
+                OnEnter(TurnState::EnemySpawn),
+                (enemy_spawn_update, refill_action_points),
+            )
+            // Phases with a short pause when entered.
+            .add_systems(
+                OnEnter(TurnState::MovePlayer),
+                start_turn_timer(MOVE_DELAY_SECONDS),
+            )
+            .add_systems(
+                OnEnter(TurnState::MoveEnemy),
+                start_turn_timer(MOVE_DELAY_SECONDS),
+            )
+            .add_systems(
+                OnEnter(EnemyAttackPhase::Windup),
+                start_turn_timer(ENEMY_ATTACK_WINDUP_SECONDS),
+            )
+            .add_systems(
+                OnEnter(EnemyAttackPhase::Cooldown),
+                (
+                    enemy_attack_update,
+                    start_turn_timer(ENEMY_ATTACK_COOLDOWN_SECONDS),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    tick_turn_timer,
+                    (
+                        move_player_update.run_if(in_state(TurnState::MovePlayer)),
+                        attack_player_update.run_if(in_state(TurnState::AttackPlayer)),
+                        move_enemy_update.run_if(in_state(TurnState::MoveEnemy)),
+                        enemy_windup_update.run_if(in_state(EnemyAttackPhase::Windup)),
+                        enemy_cooldown_update.run_if(in_state(EnemyAttackPhase::Cooldown)),
+                    ),
+                )
+                    .chain()
+                    .in_set(TurnSystems)
+                    .run_if(in_state(PauseState::Running)),
+            );
+    }
 }
 
-impl StateEntryDelay {
-    // Reset the delay whenever the turn state changes.
-    fn on_state_change(&mut self, state: TurnState) {
-        if self.last_state != Some(state) {
-            self.last_state = Some(state);
-            self.timer = None;
-        }
+// Returns a system that restarts the turn timer, used when entering a phase.
+fn start_turn_timer(seconds: f32) -> impl FnMut(ResMut<TurnTimer>) {
+    move |mut turn_timer: ResMut<TurnTimer>| {
+        turn_timer.0 = Timer::from_seconds(seconds, TimerMode::Once);
     }
+}
 
-    // Non-blocking wait: returns true once the delay has elapsed.
-    fn wait(&mut self, time: &Time, seconds: f32) -> bool {
-        if seconds <= 0.0 {
-            return true;
-        }
-        match self.timer.as_mut() {
-            Some(timer) => {
-                // Tick the timer until it finishes; stays finished until state changes.
-                timer.tick(time.delta());
-                timer.is_finished()
-            }
-            None => {
-                // First call in this state: start the timer and pause this frame.
-                self.timer = Some(Timer::from_seconds(seconds, TimerMode::Once));
-                false
-            }
-        }
+fn tick_turn_timer(time: Res<Time>, mut turn_timer: ResMut<TurnTimer>) {
+    turn_timer.tick(time.delta());
+}
+
+fn reset_run(
+    mut abilities: ResMut<PlayerAbilities>,
+    mut turn_count: ResMut<TurnCount>,
+    mut outcome: ResMut<RunOutcome>,
+    mut undos: ResMut<UndosLeft>,
+    resume: Option<Res<ResumeRun>>,
+) {
+    *abilities = PlayerAbilities::default();
+    *turn_count = TurnCount::default();
+    *outcome = RunOutcome::default();
+    if resume.is_none() {
+        *undos = UndosLeft::default();
     }
 }
 
-#[derive(Default, PartialEq, Eq)]
-enum EnemyAttackPhase {
-    #[default]
-    Idle,
-    Windup,
-    Cooldown,
+fn count_turn(mut turn_count: ResMut<TurnCount>) {
+    turn_count.0 += 1;
+}
+
+fn player_change_update(
+    player_q: Query<&mut Sprite, With<Player>>,
+    enemy_q: Query<&mut Sprite, (With<Enemy>, Without<Player>)>,
+    colorstate: Query<&mut RoundColorState>,
+    mut next_state: ResMut<NextState<TurnState>>,
+) {
+    update_player_color(player_q, &colorstate);
+    update_enemy_color(enemy_q, &colorstate);
+
+    next_state.set(TurnState::EnemySpawn);
+}
+
+// The wave director decides how many enemies come in this turn and of which kinds.
+// The ones that don't fit on the board are left in the wave and come in the next turn.
+fn enemy_spawn_update(
+    mut rng: ResMut<GameRng>,
+    script_handle: Res<WaveScriptHandle>,
+    scripts: Res<Assets<WaveScript>>,
+    turn_count: Res<TurnCount>,
+    mut director: ResMut<WaveDirector>,
+    mut rules: RulesAdapter,
+    mut next_state: ResMut<NextState<TurnState>>,
+) {
+    next_state.set(TurnState::MovePlayer);
+
+    let Some(script) = scripts.get(&script_handle.0) else {
+        println!("Waves not loaded.");
+        return;
+    };
+    let spawns = director.spawns_this_turn(script, turn_count.0);
+    let Some(wave) = script.waves.get(director.wave) else {
+        return;
+    };
+
+    let kinds: Vec<EnemyKind> = (0..spawns)
+        .map(|_| {
+            pick_weighted(&wave.mix, &mut **rng)
+                .or_else(|| rules.pick_enemy_kind(&mut **rng))
+                .unwrap_or_default()
+        })
+        .collect();
+    let spawned = rules.spawn_enemies(&kinds, director.wave, &mut **rng);
+    if spawned < spawns {
+        println!(
+            "No room for {} of the enemies of wave {}, they come next turn.",
+            spawns - spawned,
+            director.wave + 1
+        );
+    }
+    director.spawned += spawned;
 }
 
-#[derive(Default)]
-pub(crate) struct EnemyAttackDelay {
-    phase: EnemyAttackPhase,
-    // Timer for the current enemy attack phase (windup or cooldown).
-    timer: Option<Timer>,
+// Every turn's move starts with the full budget of the player's character.
+fn refill_action_points(
+    characters_handle: Res<CharactersHandle>,
+    characters: Res<Assets<Characters>>,
+    mut action_points: ResMut<ActionPoints>,
+) {
+    let Some(character) = characters
+        .get(&characters_handle.0)
+        .and_then(|characters| characters.player())
+    else {
+        println!("Characters not loaded.");
+        action_points.0 = 1;
+        return;
+    };
+    action_points.0 = character.action_points.max(1);
 }
 
-impl EnemyAttackDelay {
-    // Clear attack timing when leaving the enemy attack state.
-    fn reset(&mut self) {
-        self.phase = EnemyAttackPhase::Idle;
-        self.timer = None;
+fn move_player_update(
+    actions: Res<ButtonInput<InputAction>>,
+    mut clicks: MessageReader<TileClicked>,
+    turn_timer: Res<TurnTimer>,
+    mut action_points: ResMut<ActionPoints>,
+    mut rules: RulesAdapter,
+    mut next_state: ResMut<NextState<TurnState>>,
+    mut taken: MessageWriter<ActionTaken>,
+) {
+    // Clicks during the pause are dropped, not saved up for later.
+    let clicked = clicks.read().last().map(|click| Pos::new(click.0.x, click.0.y));
+
+    // Short pause before allowing player movement.
+    if !turn_timer.is_finished() {
+        return;
     }
 
-    // Non-blocking wait used by the enemy attack phases.
-    fn wait(&mut self, time: &Time, seconds: f32) -> bool {
-        if seconds <= 0.0 {
-            return true;
-        }
-        match self.timer.as_mut() {
-            Some(timer) => {
-                // Tick current phase timer until done.
-                timer.tick(time.delta());
-                if timer.is_finished() {
-                    self.timer = None;
-                    true
-                } else {
-                    false
-                }
-            }
-            None => {
-                // Start a new phase timer and pause this frame.
-                self.timer = Some(Timer::from_seconds(seconds, TimerMode::Once));
-                false
-            }
-        }
+    // The player can stop early and attack from where they are.
+    if actions.just_pressed(InputAction::EndMove) {
+        taken.write(ActionTaken(InputAction::EndMove));
+        next_state.set(TurnState::AttackPlayer);
+        return;
+    }
+
+    let direction = if actions.just_pressed(InputAction::MoveUp) {
+        MoveDirection::Up
+    } else if actions.just_pressed(InputAction::MoveDown) {
+        MoveDirection::Down
+    } else if actions.just_pressed(InputAction::MoveRight) {
+        MoveDirection::Right
+    } else if actions.just_pressed(InputAction::MoveLeft) {
+        MoveDirection::Left
+    } else if let Some(direction) = clicked
+        .zip(rules.player_pos())
+        .and_then(|(clicked, player)| direction_to(player, clicked))
+    {
+        // Clicking a tile next to the player steps onto it.
+        direction
+    } else {
+        return;
+    };
+
+    // Moves against the edge don't use up a point.
+    if rules.step(Action::MovePlayer(direction)).is_empty() {
+        return;
+    }
+    taken.write(ActionTaken(InputAction::moving(direction)));
+    action_points.0 = action_points.0.saturating_sub(1);
+    if action_points.0 == 0 {
+        next_state.set(TurnState::AttackPlayer);
     }
 }
 
-pub(crate) fn update_game_logic(
-    keys: Res<ButtonInput<KeyCode>>,
-    commands: Commands,
-    mut query: Query<&mut GlobalTurnState>,
-    time: Res<Time>,
-    mut state_entry_delay: Local<StateEntryDelay>,
-    mut enemy_attack_delay: Local<EnemyAttackDelay>,
-    asset_server: Res<AssetServer>,
-    texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut colorstate: Query<&mut RoundColorState>,
-    spectrum_q: Query<Entity, With<SpectrumElement>>,
-    mut tilemap_set: ParamSet<(
-        Query<
-            (
-                &TilemapSize,
-                &TilemapGridSize,
-                &TilemapTileSize,
-                &TilemapType,
-                &TilemapAnchor,
-            ),
-            With<PlayZoneTilemap>,
-        >,
-        Query<(&TileStorage, &TilemapSize), With<PlayZoneTilemap>>,
-    )>,
-    player_q2: Query<&mut Sprite, With<Player>>,
-    enemy_q: Query<&mut Sprite, (With<Enemy>, Without<Player>)>,
-    mut enemy_pos_q: Query<(Entity, &mut TilePos), (With<Enemy>, Without<Player>)>,
-    mut player_query: Query<(Entity, &mut TilePos), (With<Player>, Without<Enemy>)>,
-    mut tile_query: Query<&mut TileColor>,
+#[allow(clippy::too_many_arguments)]
+fn attack_player_update(
+    actions: Res<ButtonInput<InputAction>>,
+    patterns_handle: Res<AttackPatternsHandle>,
+    patterns: Res<Assets<AttackPatterns>>,
+    mut abilities: ResMut<PlayerAbilities>,
+    mut stats: ResMut<RunStats>,
+    mut rules: RulesAdapter,
+    mut next_state: ResMut<NextState<TurnState>>,
+    mut taken: MessageWriter<ActionTaken>,
+) {
+    // Attack with player, the attack actions pick a slot from the patterns asset.
+    let Some(slot) = InputAction::ATTACKS
+        .iter()
+        .position(|action| actions.just_pressed(*action))
+    else {
+        return;
+    };
+
+    let Some((id, pattern)) = patterns.get(&patterns_handle.0).and_then(|patterns| {
+        Some((patterns.player.get(slot)?, patterns.player_slot(slot)?))
+    }) else {
+        println!("Attack patterns not loaded.");
+        return;
+    };
+
+    // The turn keeps waiting for an attack that can be used.
+    if !abilities.can_use(slot, pattern) {
+        println!("Attack not ready.");
+        return;
+    }
+    abilities.use_slot(slot, pattern);
+    taken.write(ActionTaken(InputAction::ATTACKS[slot]));
+
+    let events = rules.step(Action::PlayerAttack(pattern.clone()));
+    let kills = events
+        .iter()
+        .filter(|event| matches!(event, Event::UnitKilled { .. }))
+        .count();
+    abilities.add_kills(kills as u32);
+    stats.record_attack(id, kills as u32);
+    next_state.set(TurnState::MoveEnemy);
+}
+
+fn move_enemy_update(
+    turn_timer: Res<TurnTimer>,
+    mut rules: RulesAdapter,
+    mut next_state: ResMut<NextState<TurnState>>,
 ) {
-    // Retrieve the global turn state.
-    // This component dictates what action is done next.
-    // Enemy attack, Player attack, etc.
-    for mut turn_state_entity in query.iter_mut() {
-        // Track state transitions so per-state delays only run once.
-        state_entry_delay.on_state_change(turn_state_entity.turn_state);
-        // Reset enemy attack timing when we're not in the enemy attack state.
-        if turn_state_entity.turn_state != TurnState::AttackEnemy {
-            enemy_attack_delay.reset();
-        }
-
-        match turn_state_entity.turn_state {
-            TurnState::ColorPick => {
-                color_pick_update(commands, asset_server, &mut colorstate, &spectrum_q);
-                
-                turn_state_entity.modify_state(TurnState::PlayerChange);
-                return;
-            }
-            TurnState::PlayerChange => {
-                update_player_color(player_q2, &colorstate);
-                update_enemy_color(enemy_q, &colorstate);
-
-                turn_state_entity.modify_state(TurnState::EnemySpawn);
-                return;
-            }
-            TurnState::EnemySpawn => {
-                let mut rng = rand::rng();
-                let mut spawn_pos = None;
-                for _ in 0..8 {
-                    let x = rng.random_range(0..15) as u32;
-                    let y = rng.random_range(13..15) as u32;
-                    let occupied = enemy_pos_q
-                        .iter_mut()
-                        .any(|(_, pos)| pos.x == x && pos.y == y);
-                    if !occupied {
-                        spawn_pos = Some((x, y));
-                        break;
-                    }
-                }
-
-                if let Some((x, y)) = spawn_pos {
-                    spawn_enemy(x, y, commands, asset_server, texture_atlas_layouts);
-                }
-                turn_state_entity.modify_state(TurnState::MovePlayer);
-                return;
-            }
-            TurnState::MovePlayer => {
-                // Short pause before allowing player movement.
-                if !state_entry_delay.wait(&time, MOVE_DELAY_SECONDS) {
-                    return;
-                }
-                let tilemap_q = tilemap_set.p0();
-                let Ok((map_size, _grid_size, _tile_size, _map_type, _anchor)) =
-                    tilemap_q.single()
-                else {
-                    return;
-                };
-
-                for (_, mut tile_pos) in player_query.iter_mut() {
-                    let mut next = *tile_pos;
-
-                    if keys.just_pressed(KeyCode::ArrowUp) {
-                        next.y += 1;
-                        turn_state_entity.modify_state(TurnState::AttackPlayer);
-                    }
-                    if keys.just_pressed(KeyCode::ArrowDown) {
-                        next.y = next.y.saturating_sub(1);
-                        turn_state_entity.modify_state(TurnState::AttackPlayer);
-                    }
-                    if keys.just_pressed(KeyCode::ArrowRight) {
-                        next.x += 1;
-                        turn_state_entity.modify_state(TurnState::AttackPlayer);
-                    }
-                    if keys.just_pressed(KeyCode::ArrowLeft) {
-                        next.x = next.x.saturating_sub(1);
-                        turn_state_entity.modify_state(TurnState::AttackPlayer);
-                    }
-
-                    let max_x = (map_size.x - 1) as i32;
-                    let max_y = (map_size.y - 1) as i32;
-
-                    next.x = (next.x as i32).clamp(0, max_x) as u32;
-                    next.y = (next.y as i32).clamp(0, max_y) as u32;
-
-                    *tile_pos = next;
-                }
-                return;
-            }
-            TurnState::AttackPlayer => {
-                // Attack with player
-                if keys.just_pressed(KeyCode::KeyQ) {
-                    color_player_neighbors(
-                        AttackPattern::Diagonal,
-                        &mut player_query,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    despawn_enemies_on_matching_tile_color(
-                        commands,
-                        &mut enemy_pos_q,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    turn_state_entity.modify_state(TurnState::MoveEnemy);
-                } else if keys.just_pressed(KeyCode::KeyW) {
-                    color_player_neighbors(
-                        AttackPattern::Sides,
-                        &mut player_query,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    despawn_enemies_on_matching_tile_color(
-                        commands,
-                        &mut enemy_pos_q,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    turn_state_entity.modify_state(TurnState::MoveEnemy);
-                } else if keys.just_pressed(KeyCode::KeyE) {
-                    color_player_neighbors(
-                        AttackPattern::Around,
-                        &mut player_query,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    despawn_enemies_on_matching_tile_color(
-                        commands,
-                        &mut enemy_pos_q,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    turn_state_entity.modify_state(TurnState::MoveEnemy);
-                } else if keys.just_pressed(KeyCode::KeyR) {
-                    color_player_neighbors(
-                        AttackPattern::Ultimate,
-                        &mut player_query,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    despawn_enemies_on_matching_tile_color(
-                        commands,
-                        &mut enemy_pos_q,
-                        tilemap_set.p1(),
-                        &mut tile_query,
-                        &colorstate,
-                    );
-                    turn_state_entity.modify_state(TurnState::MoveEnemy);
-                }
-
-                return;
-            }
-            TurnState::MoveEnemy => {
-                // Short pause before enemy movement.
-                if !state_entry_delay.wait(&time, MOVE_DELAY_SECONDS) {
-                    return;
-                }
-                for (_, mut tile_pos) in enemy_pos_q.iter_mut() {
-                    tile_pos.y = tile_pos.y.saturating_sub(1);
-                }
-                turn_state_entity.modify_state(TurnState::AttackEnemy);
-                return;
-            }
-            TurnState::AttackEnemy => {
-                match enemy_attack_delay.phase {
-                    EnemyAttackPhase::Idle => {
-                        // Start the windup phase on first entry.
-                        enemy_attack_delay.phase = EnemyAttackPhase::Windup;
-                        enemy_attack_delay.timer = None;
-                        return;
-                    }
-                    EnemyAttackPhase::Windup => {
-                        // Windup delay before the enemy attack happens.
-                        if !enemy_attack_delay.wait(&time, ENEMY_ATTACK_WINDUP_SECONDS) {
-                            return;
-                        }
-                        color_enemy_neighbors(
-                            &mut enemy_pos_q,
-                            tilemap_set.p1(),
-                            &mut tile_query,
-                            &colorstate,
-                        );
-                        despawn_player_on_matching_tile_color(
-                            commands,
-                            &mut player_query,
-                            tilemap_set.p1(),
-                            &mut tile_query,
-                            &colorstate,
-                        );
-                        // After attacking, enter cooldown phase.
-                        enemy_attack_delay.phase = EnemyAttackPhase::Cooldown;
-                        enemy_attack_delay.timer = None;
-                        return;
-                    }
-                    EnemyAttackPhase::Cooldown => {
-                        // Cooldown delay after the attack, before the next turn.
-                        if !enemy_attack_delay.wait(&time, ENEMY_ATTACK_COOLDOWN_SECONDS) {
-                            return;
-                        }
-                        enemy_attack_delay.reset();
-                        turn_state_entity.modify_state(TurnState::ColorPick);
-                        return;
-                    }
-                }
-            }
-        }
+    // Short pause before enemy movement.
+    if !turn_timer.is_finished() {
+        return;
+    }
+    // Enemies hunt for a tile their attack reaches the player from.
+    rules.step(Action::MoveEnemies);
+    next_state.set(TurnState::AttackEnemy);
+}
+
+fn enemy_windup_update(
+    turn_timer: Res<TurnTimer>,
+    mut next_phase: ResMut<NextState<EnemyAttackPhase>>,
+) {
+    // Windup delay before the enemy attack happens.
+    if turn_timer.is_finished() {
+        next_phase.set(EnemyAttackPhase::Cooldown);
+    }
+}
+
+// Runs once when the windup is over, the cooldown gives the player time to see the result.
+fn enemy_attack_update(mut rules: RulesAdapter) {
+    rules.step(Action::EnemiesAttack);
+}
+
+fn enemy_cooldown_update(
+    turn_timer: Res<TurnTimer>,
+    player_q: Query<(), With<Player>>,
+    mut stats: ResMut<RunStats>,
+    mut next_state: ResMut<NextState<TurnState>>,
+    mut next_app_state: ResMut<NextState<ApplicationState>>,
+) {
+    // Cooldown delay after the attack, before the next turn.
+    if !turn_timer.is_finished() {
+        return;
+    }
+    // The enemies killed the player, show the end screen.
+    if player_q.is_empty() {
+        next_app_state.set(ApplicationState::EndMenu);
+    } else {
+        stats.record_turn_survived();
+        next_state.set(TurnState::ColorPick);
     }
 }
 
 fn color_pick_update(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    color_state_q: &mut Query<&mut RoundColorState>,
-    spectrum_q: &Query<Entity, With<SpectrumElement>>,
+    mut rng: ResMut<GameRng>,
+    mut color_state_q: Query<&mut RoundColorState>,
+    spectrum_q: Query<Entity, With<SpectrumElement>>,
+    mut stats: ResMut<RunStats>,
+    mut next_state: ResMut<NextState<TurnState>>,
 ) {
-    let offset_up = 178.0f32;
-    let offset_right = 61.0f32;
-
     // Despawn the color sprites from before
     // There might be smarter ways to update existing sprites though
     for entity in spectrum_q.iter() {
@@ -350,98 +345,113 @@ fn color_pick_update(
     }
 
     for mut color_state in color_state_q.iter_mut() {
-        color_state.asign_random_color();
-
-        // Load Spectrum Sprites
-        let texture_handle_blue: bevy::prelude::Handle<Image> =
-            asset_server.load("Spectrum-Blue-Sphere.png");
-        let texture_handle_red: bevy::prelude::Handle<Image> =
-            asset_server.load("Spectrum-Red-Sphere.png");
-        let texture_handle_green: bevy::prelude::Handle<Image> =
-            asset_server.load("Spectrum-Green-Sphere.png");
-        let texture_handle_middle: bevy::prelude::Handle<Image> = asset_server.load("TEXT.png");
-        let texture_handle_arrow: bevy::prelude::Handle<Image> =
-            asset_server.load("Spectrum-Masked.png");
-
-        if color_state.index != 0
-        // Red gets masked
-        {
-            // The colors in the spectrum
-            commands.spawn((
-                Sprite {
-                    image: texture_handle_red,
-                    color: Color::srgba(1.0, 0.0, 0.0, 0.7),
-                    ..default()
-                },
-                Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
-                SpectrumElement,
-            ));
-        }
-        if color_state.index != 1 {
-            commands.spawn((
-                Sprite {
-                    image: texture_handle_green,
-                    color: Color::srgba(0.0, 1.0, 0.0, 0.7),
-                    ..default()
-                },
-                Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
-                SpectrumElement,
-            ));
-        }
-        if color_state.index != 2 {
-            commands.spawn((
-                Sprite {
-                    image: texture_handle_blue.clone(),
-                    color: Color::srgba(0.0, 0.0, 1.0, 0.7),
-                    ..default()
-                },
-                Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
-                SpectrumElement,
-            ));
-        }
-
-        // Arrow that shows what gets masked
-        let mut r = 0.0f32;
-        let mut g = 0.0f32;
-        let mut b = 0.0f32;
-        let mut r2 = 1.0f32;
-        let mut g2 = 1.0f32;
-        let mut b2 = 1.0f32;
-
-        if color_state.index == 0 {
-            r = 1.0f32;
-            r2 = 0.0f32;
-        } else if color_state.index == 1 {
-            g = 1.0f32;
-            g2 = 0.0f32;
-        } else {
-            b = 1.0f32;
-            b2 = 0.0f32;
-        }
+        color_state.asign_random_color(&mut **rng);
+        stats.record_color(RoundColor::from_index(color_state.index));
+        spawn_spectrum(&mut commands, &asset_server, color_state.index);
+    }
+
+    next_state.set(TurnState::PlayerChange);
+}
 
+// The spectrum at the top of the screen showing which color is masked this round.
+pub fn spawn_spectrum(commands: &mut Commands, asset_server: &AssetServer, index: i32) {
+    let offset_up = 178.0f32;
+    let offset_right = 61.0f32;
+
+    // Load Spectrum Sprites
+    let texture_handle_blue: bevy::prelude::Handle<Image> =
+        asset_server.load("Spectrum-Blue-Sphere.png");
+    let texture_handle_red: bevy::prelude::Handle<Image> =
+        asset_server.load("Spectrum-Red-Sphere.png");
+    let texture_handle_green: bevy::prelude::Handle<Image> =
+        asset_server.load("Spectrum-Green-Sphere.png");
+    let texture_handle_middle: bevy::prelude::Handle<Image> = asset_server.load("TEXT.png");
+    let texture_handle_arrow: bevy::prelude::Handle<Image> =
+        asset_server.load("Spectrum-Masked.png");
+
+    if index != 0
+    // Red gets masked
+    {
+        // The colors in the spectrum
         commands.spawn((
             Sprite {
-                image: texture_handle_middle.clone(),
-                color: Color::srgba(r, g, b, 1.),
+                image: texture_handle_red,
+                color: Color::srgba(1.0, 0.0, 0.0, 0.7),
                 ..default()
             },
-            Transform::from_xyz(
-                offset_right - 55 as f32,
-                offset_up - 8 as f32,
-                LAYER_UI as f32,
-            ),
+            Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
             SpectrumElement,
+            DespawnOnExit(ApplicationState::Gameplay),
         ));
-
-        // Round Resulting color
+    }
+    if index != 1 {
+        commands.spawn((
+            Sprite {
+                image: texture_handle_green,
+                color: Color::srgba(0.0, 1.0, 0.0, 0.7),
+                ..default()
+            },
+            Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
+            SpectrumElement,
+            DespawnOnExit(ApplicationState::Gameplay),
+        ));
+    }
+    if index != 2 {
         commands.spawn((
-            Transform::from_xyz(offset_right, offset_up - 8 as f32, LAYER_UI as f32),
             Sprite {
-                image: texture_handle_arrow.clone(),
-                color: Color::srgba(r2, g2, b2, 1.),
+                image: texture_handle_blue.clone(),
+                color: Color::srgba(0.0, 0.0, 1.0, 0.7),
                 ..default()
             },
+            Transform::from_xyz(-offset_right, offset_up, LAYER_UI as f32),
             SpectrumElement,
+            DespawnOnExit(ApplicationState::Gameplay),
         ));
     }
+
+    // Arrow that shows what gets masked
+    let mut r = 0.0f32;
+    let mut g = 0.0f32;
+    let mut b = 0.0f32;
+    let mut r2 = 1.0f32;
+    let mut g2 = 1.0f32;
+    let mut b2 = 1.0f32;
+
+    if index == 0 {
+        r = 1.0f32;
+        r2 = 0.0f32;
+    } else if index == 1 {
+        g = 1.0f32;
+        g2 = 0.0f32;
+    } else {
+        b = 1.0f32;
+        b2 = 0.0f32;
+    }
+
+    commands.spawn((
+        Sprite {
+            image: texture_handle_middle.clone(),
+            color: Color::srgba(r, g, b, 1.),
+            ..default()
+        },
+        Transform::from_xyz(
+            offset_right - 55 as f32,
+            offset_up - 8 as f32,
+            LAYER_UI as f32,
+        ),
+        SpectrumElement,
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
+
+    // Round Resulting color
+    commands.spawn((
+        Transform::from_xyz(offset_right, offset_up - 8 as f32, LAYER_UI as f32),
+        Sprite {
+            image: texture_handle_arrow.clone(),
+            color: Color::srgba(r2, g2, b2, 1.),
+            ..default()
+        },
+        SpectrumElement,
+        DespawnOnExit(ApplicationState::Gameplay),
+    ));
 }
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::*;
+use crate::ron_asset::RonAssetLoader;
+use crate::rules::EnemyKind;
+
+pub const WAVES_PATH: &str = "waves.ron";
+
+// One wave of the script in assets/waves.ron.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Wave {
+    // Enemies in the wave before difficulty scaling.
+    pub count: u32,
+    // Enemies spawned every turn until the whole wave is out.
+    #[serde(default = "one")]
+    pub per_turn: u32,
+    // Kinds with their relative chance, empty uses the spawn weights of assets/enemy_kinds.ron.
+    #[serde(default)]
+    pub mix: Vec<(EnemyKind, u32)>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct WaveScript {
+    pub waves: Vec<Wave>,
+    // Turns without spawns after a wave is cleared.
+    pub intermission: u32,
+    // Every this many turns played the next waves get one more enemy, 0 turns scaling off.
+    #[serde(default)]
+    pub extra_enemy_every: u32,
+}
+
+impl WaveScript {
+    // Enemies in a wave once the turns played so far are taken into account.
+    pub fn wave_size(&self, wave: usize, turn: u32) -> u32 {
+        let Some(wave) = self.waves.get(wave) else {
+            return 0;
+        };
+        let extra = turn.checked_div(self.extra_enemy_every).unwrap_or(0);
+        wave.count + extra
+    }
+}
+
+#[derive(Resource)]
+pub struct WaveScriptHandle(pub Handle<WaveScript>);
+
+// Where the run is in the wave script.
+#[derive(Resource, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WaveDirector {
+    // Index of the current wave.
+    pub wave: usize,
+    pub size: u32,
+    pub spawned: u32,
+    // Turns left before the current wave starts spawning.
+    pub intermission: u32,
+    started: bool,
+}
+
+impl WaveDirector {
+    // How many enemies to spawn this turn, starting the wave on its first turn.
+    pub fn spawns_this_turn(&mut self, script: &WaveScript, turn: u32) -> u32 {
+        if self.intermission > 0 {
+            self.intermission -= 1;
+            return 0;
+        }
+        let Some(wave) = script.waves.get(self.wave) else {
+            return 0;
+        };
+        if !self.started {
+            self.started = true;
+            self.spawned = 0;
+            self.size = script.wave_size(self.wave, turn);
+        }
+        wave.per_turn.min(self.size.saturating_sub(self.spawned))
+    }
+
+    // Spawn phases left before the current wave starts, None once it has.
+    pub fn turns_until_wave(&self) -> Option<u32> {
+        (!self.started).then_some(self.intermission + 1)
+    }
+
+    // Every enemy of the wave has been spawned.
+    pub fn is_wave_out(&self) -> bool {
+        self.started && self.spawned >= self.size
+    }
+
+    // Moves on to the next wave after an intermission, false when that was the last one.
+    pub fn finish_wave(&mut self, script: &WaveScript) -> bool {
+        self.wave += 1;
+        self.started = false;
+        self.intermission = script.intermission;
+        self.wave < script.waves.len()
+    }
+}
+
+// Written when the last enemy of a wave dies.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaveCleared {
+    pub wave: usize,
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<WaveScript>()
+            .init_asset_loader::<RonAssetLoader<WaveScript>>()
+            .init_resource::<WaveDirector>()
+            .add_message::<WaveCleared>()
+            .add_systems(Startup, load_wave_script)
+            .add_systems(OnEnter(ApplicationState::Gameplay), reset_waves)
+            // Enemies die in the player's attack or drown in a pool while they move,
+            // so this is checked right after each.
+            .add_systems(OnEnter(TurnState::MoveEnemy), check_wave_cleared)
+            .add_systems(OnEnter(TurnState::AttackEnemy), check_wave_cleared)
+            .add_systems(Update, log_wave_cleared);
+    }
+}
+
+fn load_wave_script(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(WaveScriptHandle(asset_server.load(WAVES_PATH)));
+}
+
+fn reset_waves(mut director: ResMut<WaveDirector>) {
+    *director = WaveDirector::default();
+}
+
+fn check_wave_cleared(
+    script_handle: Res<WaveScriptHandle>,
+    scripts: Res<Assets<WaveScript>>,
+    enemy_q: Query<(), With<Enemy>>,
+    mut director: ResMut<WaveDirector>,
+    mut wave_cleared: MessageWriter<WaveCleared>,
+    mut outcome: ResMut<RunOutcome>,
+    mut next_app_state: ResMut<NextState<ApplicationState>>,
+) {
+    if !director.is_wave_out() || !enemy_q.is_empty() {
+        return;
+    }
+    let Some(script) = scripts.get(&script_handle.0) else {
+        println!("Waves not loaded.");
+        return;
+    };
+
+    wave_cleared.write(WaveCleared {
+        wave: director.wave,
+    });
+    // Surviving the whole script wins the run.
+    if !director.finish_wave(script) {
+        *outcome = RunOutcome::Victory;
+        next_app_state.set(ApplicationState::EndMenu);
+    }
+}
+
+fn log_wave_cleared(mut wave_cleared: MessageReader<WaveCleared>) {
+    for cleared in wave_cleared.read() {
+        println!("Wave {} cleared.", cleared.wave + 1);
+    }
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+
+// Starts a game with only the player on the board, ready to attack.
+fn start_attack() -> Harness {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness
+}
+
+// Lets the enemies take their turn and runs the next one until the player can attack again.
+fn next_attack(harness: &mut Harness) {
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowDown);
+    harness.press(KeyCode::Space);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+}
+
+#[test]
+fn ultimate_needs_charges() {
+    let mut harness = start_attack();
+
+    harness.press(KeyCode::KeyR);
+
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+    assert_eq!(harness.tile_color(TilePos { x: 8, y: 4 }), Some(Color::WHITE));
+}
+
+#[test]
+fn ultimate_paints_row_column_and_area_for_charges() {
+    let mut harness = start_attack();
+    harness.set_charges(3);
+
+    harness.press(KeyCode::KeyR);
+
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+    for pos in [
+        TilePos { x: 0, y: 4 },
+        TilePos { x: 15, y: 4 },
+        TilePos { x: 8, y: 15 },
+        TilePos { x: 8, y: 0 },
+        TilePos { x: 10, y: 6 },
+        TilePos { x: 6, y: 2 },
+    ] {
+        assert_ne!(harness.tile_color(pos), Some(Color::WHITE));
+    }
+    assert_eq!(harness.tile_color(TilePos { x: 11, y: 7 }), Some(Color::WHITE));
+
+    let abilities = harness.abilities();
+    assert_eq!(abilities.charges, 0);
+    assert_eq!(abilities.cooldown(3), 3);
+}
+
+#[test]
+fn kills_earn_charges() {
+    let mut harness = start_attack();
+    harness.spawn_enemy(TilePos { x: 10, y: 4 });
+    harness.spawn_enemy(TilePos { x: 8, y: 7 });
+
+    harness.press(KeyCode::KeyW);
+
+    assert!(harness.enemy_positions().is_empty());
+    assert_eq!(harness.abilities().charges, 2);
+}
+
+#[test]
+fn attack_on_cooldown_waits_for_another_key() {
+    let mut harness = start_attack();
+    harness.press(KeyCode::KeyW);
+    assert_eq!(harness.abilities().cooldown(1), 1);
+    harness.clear_enemies();
+    next_attack(&mut harness);
+
+    harness.press(KeyCode::KeyW);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+
+    // Using another attack ticks the cooldown down.
+    harness.press(KeyCode::KeyQ);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+    assert_eq!(harness.abilities().cooldown(1), 0);
+}
@@ -0,0 +1,393 @@
+// Not every test file uses every helper.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use bevy::image::TextureAtlasPlugin;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::touch::Touches;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy_ecs_tiled::prelude::*;
+
+use global_game_jam_2026::GamePlugin;
+use global_game_jam_2026::attack_patterns::*;
+use global_game_jam_2026::characters::*;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::enemy_kinds::*;
+use global_game_jam_2026::high_scores::HighScorePath;
+use global_game_jam_2026::input::KeyBindingsPath;
+use global_game_jam_2026::playzone::PlayzoneHandle;
+use global_game_jam_2026::pointer::{AttackPreview, HoveredTile, TileClicked};
+use global_game_jam_2026::replay::ReplayPath;
+use global_game_jam_2026::rules::Abilities;
+use global_game_jam_2026::save_game::{ResumeRun, SaveGame, SavePath};
+use global_game_jam_2026::waves::*;
+
+// Every update advances the clock by this much.
+pub const FRAME: Duration = Duration::from_millis(50);
+
+// Fixed seed so color picks and enemy spawns are the same on every run.
+pub const TEST_SEED: u64 = 2026;
+
+// Every harness saves to its own directory, never to the player's.
+static HARNESS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn temp_data_dir() -> PathBuf {
+    let count = HARNESS_COUNT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!(
+        "color-wizard-test-{}-{}",
+        std::process::id(),
+        count
+    ))
+}
+
+// Headless app that runs the game systems without a window, renderer or post processing.
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Harness::with_seed(TEST_SEED)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let data_dir = temp_data_dir();
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+            TextureAtlasPlugin,
+        ))
+        .init_asset::<Font>()
+        // No input plugin, the tests press and release keys themselves.
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<Touches>()
+        .add_message::<KeyboardInput>()
+        .insert_resource(HighScorePath(data_dir.join("high_scores.ron")))
+        .insert_resource(SavePath(data_dir.join("save.ron")))
+        .insert_resource(KeyBindingsPath(data_dir.join("keybindings.ron")))
+        .insert_resource(ReplayPath(data_dir.join("last_replay.ron")))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(GameRng::from_seed(seed))
+        .add_plugins(TilemapPlugin)
+        .add_plugins(TiledPlugin::default())
+        .add_plugins(GamePlugin);
+
+        // Runs the startup schedules.
+        app.update();
+
+        let mut harness = Harness { app };
+        harness.wait_for_data_assets();
+        harness
+    }
+
+    // The RON assets and the playzone map load on another thread, nothing works until they are there.
+    fn wait_for_data_assets(&mut self) {
+        for _ in 0..500 {
+            let world = self.app.world();
+            let patterns = &world.resource::<AttackPatternsHandle>().0;
+            let characters = &world.resource::<CharactersHandle>().0;
+            let enemy_kinds = &world.resource::<EnemyKindsHandle>().0;
+            let waves = &world.resource::<WaveScriptHandle>().0;
+            let playzone = &world.resource::<PlayzoneHandle>().0;
+            if world
+                .resource::<Assets<AttackPatterns>>()
+                .contains(patterns)
+                && world.resource::<Assets<Characters>>().contains(characters)
+                && world.resource::<Assets<EnemyKinds>>().contains(enemy_kinds)
+                && world.resource::<Assets<WaveScript>>().contains(waves)
+                && world.resource::<Assets<TiledMapAsset>>().contains(playzone)
+            {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            self.app.update();
+        }
+        panic!("data assets never loaded");
+    }
+
+    // Leaves the main menu and runs the instant phases until the player can move.
+    pub fn start_gameplay(&mut self) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<ApplicationState>>()
+            .set(ApplicationState::Gameplay);
+        assert!(
+            self.run_until(TurnState::MovePlayer, 10),
+            "gameplay never reached MovePlayer"
+        );
+    }
+
+    // Continues a saved run the way the Continue button does.
+    pub fn continue_run(&mut self, save: SaveGame) {
+        let phase = save.phase;
+        self.app.insert_resource(ResumeRun(save));
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<ApplicationState>>()
+            .set(ApplicationState::Gameplay);
+        assert!(
+            self.run_until(phase, 10),
+            "saved run never reached {:?}",
+            phase
+        );
+    }
+
+    pub fn saved_game(&self) -> Option<SaveGame> {
+        SaveGame::load(&self.app.world().resource::<SavePath>().0)
+    }
+
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    // Runs enough frames for the given amount of seconds to pass.
+    pub fn advance(&mut self, seconds: f32) {
+        let frames = (seconds / FRAME.as_secs_f32()).ceil() as usize + 1;
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    // Holds the key for one frame, then releases it and runs one more frame so state changes apply.
+    pub fn press(&mut self, key: KeyCode) {
+        self.keys().press(key);
+        self.app.update();
+        let mut keys = self.keys();
+        keys.release(key);
+        keys.clear();
+        self.app.update();
+    }
+
+    // Holds a gamepad button for one frame the same way press does with a key.
+    pub fn press_button(&mut self, button: GamepadButton) {
+        self.gamepad().digital_mut().press(button);
+        self.app.update();
+        let mut gamepad = self.gamepad();
+        gamepad.digital_mut().release(button);
+        gamepad.digital_mut().clear();
+        self.app.update();
+    }
+
+    // Leaves the left stick where it is until the next call, zero puts it back in the middle.
+    pub fn tilt_stick(&mut self, stick: Vec2) {
+        let mut gamepad = self.gamepad();
+        gamepad.analog_mut().set(GamepadAxis::LeftStickX, stick.x);
+        gamepad.analog_mut().set(GamepadAxis::LeftStickY, stick.y);
+        self.app.update();
+    }
+
+    // No window either, the tile under the cursor is set directly.
+    pub fn hover(&mut self, tile: Option<TilePos>) {
+        self.app.insert_resource(HoveredTile(tile));
+        self.app.update();
+    }
+
+    pub fn click_tile(&mut self, tile: TilePos) {
+        self.app.insert_resource(HoveredTile(Some(tile)));
+        self.app.world_mut().write_message(TileClicked(tile));
+        self.app.update();
+        self.app.update();
+    }
+
+    // Left click with the pointer over the tile, the way the mouse does it rather than sending the message.
+    // With a button given, the click lands on that button too.
+    pub fn click_pointer(&mut self, tile: TilePos, button: Option<ButtonAction>) {
+        self.app.insert_resource(HoveredTile(Some(tile)));
+        if let Some(action) = button {
+            let button = self.button(action);
+            self.app
+                .world_mut()
+                .entity_mut(button)
+                .insert(Interaction::Pressed);
+        }
+        self.mouse().press(MouseButton::Left);
+        self.app.update();
+        let mut mouse = self.mouse();
+        mouse.release(MouseButton::Left);
+        mouse.clear();
+        self.app.update();
+    }
+
+    fn mouse(&mut self) -> Mut<'_, ButtonInput<MouseButton>> {
+        self.app
+            .world_mut()
+            .resource_mut::<ButtonInput<MouseButton>>()
+    }
+
+    // Tiles the hover preview shows for every attack slot.
+    pub fn attack_preview(&mut self) -> Vec<(usize, TilePos)> {
+        let world = self.app.world_mut();
+        let mut previews: Vec<(usize, TilePos)> = world
+            .query::<&AttackPreview>()
+            .iter(world)
+            .map(|preview| (preview.slot, preview.tile))
+            .collect();
+        previews.sort_by_key(|(slot, tile)| (*slot, tile.x, tile.y));
+        previews
+    }
+
+    // Presses the first button with the action, no UI plugin runs so the interaction is set directly.
+    pub fn click(&mut self, action: ButtonAction) {
+        let button = self.button(action);
+        self.app
+            .world_mut()
+            .entity_mut(button)
+            .insert(Interaction::Pressed);
+        self.app.update();
+        self.app.update();
+    }
+
+    fn button(&mut self, action: ButtonAction) -> Entity {
+        let world = self.app.world_mut();
+        world
+            .query::<(Entity, &ButtonAction)>()
+            .iter(world)
+            .find(|(_, other)| **other == action)
+            .map(|(entity, _)| entity)
+            .expect("no button with that action")
+    }
+
+    pub fn run_until(&mut self, state: TurnState, max_frames: usize) -> bool {
+        for _ in 0..max_frames {
+            if self.turn_state() == Some(state) {
+                return true;
+            }
+            self.app.update();
+        }
+        self.turn_state() == Some(state)
+    }
+
+    pub fn app_state(&self) -> ApplicationState {
+        *self.app.world().resource::<State<ApplicationState>>().get()
+    }
+
+    pub fn turn_state(&self) -> Option<TurnState> {
+        self.app
+            .world()
+            .get_resource::<State<TurnState>>()
+            .map(|state| *state.get())
+    }
+
+    pub fn pause_state(&self) -> Option<PauseState> {
+        self.app
+            .world()
+            .get_resource::<State<PauseState>>()
+            .map(|state| *state.get())
+    }
+
+    pub fn round_color_index(&mut self) -> i32 {
+        let world = self.app.world_mut();
+        world
+            .query::<&RoundColorState>()
+            .single(world)
+            .expect("no round color state")
+            .index
+    }
+
+    pub fn player_pos(&mut self) -> Option<TilePos> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&TilePos, With<Player>>()
+            .single(world)
+            .ok()
+            .copied()
+    }
+
+    pub fn set_player_pos(&mut self, pos: TilePos) {
+        let world = self.app.world_mut();
+        let mut player = world
+            .query_filtered::<&mut TilePos, With<Player>>()
+            .single_mut(world)
+            .expect("no player");
+        *player = pos;
+    }
+
+    pub fn player_health(&mut self) -> Option<u32> {
+        let world = self.app.world_mut();
+        world
+            .query_filtered::<&Health, With<Player>>()
+            .single(world)
+            .ok()
+            .map(|health| health.0)
+    }
+
+    pub fn set_player_health(&mut self, health: u32) {
+        let world = self.app.world_mut();
+        let mut player = world
+            .query_filtered::<&mut Health, With<Player>>()
+            .single_mut(world)
+            .expect("no player");
+        player.0 = health;
+    }
+
+    pub fn enemy_positions(&mut self) -> Vec<TilePos> {
+        let world = self.app.world_mut();
+        let mut positions: Vec<TilePos> = world
+            .query_filtered::<&TilePos, With<Enemy>>()
+            .iter(world)
+            .copied()
+            .collect();
+        positions.sort_by_key(|pos| (pos.x, pos.y));
+        positions
+    }
+
+    pub fn clear_enemies(&mut self) {
+        let world = self.app.world_mut();
+        let enemies: Vec<Entity> = world
+            .query_filtered::<Entity, With<Enemy>>()
+            .iter(world)
+            .collect();
+        for enemy in enemies {
+            world.despawn(enemy);
+        }
+    }
+
+    // Bare enemy without sprite, enough for the rules.
+    pub fn spawn_enemy(&mut self, pos: TilePos) {
+        self.app.world_mut().spawn((Troop, Enemy, pos));
+    }
+
+    pub fn tile_color(&mut self, pos: TilePos) -> Option<Color> {
+        let world = self.app.world_mut();
+        let storage = world
+            .query_filtered::<&TileStorage, With<PlayZoneTilemap>>()
+            .single(world)
+            .ok()?;
+        let tile_entity = storage.get(&pos)?;
+        world.get::<TileColor>(tile_entity).map(|color| color.0)
+    }
+
+    pub fn abilities(&self) -> Abilities {
+        self.app.world().resource::<PlayerAbilities>().0.clone()
+    }
+
+    pub fn set_charges(&mut self, charges: u32) {
+        self.app
+            .world_mut()
+            .resource_mut::<PlayerAbilities>()
+            .charges = charges;
+    }
+
+    // No gamepad plugin runs either, the first use connects one that the tests drive directly.
+    fn gamepad(&mut self) -> Mut<'_, Gamepad> {
+        let world = self.app.world_mut();
+        let entity = match world.query_filtered::<Entity, With<Gamepad>>().iter(world).next() {
+            Some(entity) => entity,
+            None => world.spawn(Gamepad::default()).id(),
+        };
+        world.get_mut::<Gamepad>(entity).unwrap()
+    }
+
+    fn keys(&mut self) -> Mut<'_, ButtonInput<KeyCode>> {
+        self.app.world_mut().resource_mut::<ButtonInput<KeyCode>>()
+    }
+}
@@ -0,0 +1,149 @@
+use global_game_jam_2026::enemy_ai::*;
+use global_game_jam_2026::rules::*;
+
+fn enemy_sides() -> AttackPattern {
+    AttackPattern {
+        rays: vec![(1, 0), (-1, 0), (0, 1), (0, -1)],
+        range: 2,
+        ..Default::default()
+    }
+}
+
+fn game_with_player(x: u32, y: u32) -> Game {
+    let mut game = Game::new(Board::default(), RoundColor::Red);
+    game.roster.insert(
+        EnemyKind::Grunt,
+        EnemyRules {
+            pattern: enemy_sides(),
+            ..Default::default()
+        },
+    );
+    game.add_unit(Side::Player, Pos::new(x, y));
+    game
+}
+
+fn add_grunt(game: &mut Game, x: u32, y: u32) -> UnitId {
+    game.add_enemy(EnemyKind::Grunt, Pos::new(x, y), 1)
+}
+
+fn enemy_positions(game: &Game) -> Vec<Pos> {
+    game.enemies().map(|enemy| enemy.pos).collect()
+}
+
+#[test]
+fn enemy_walks_until_its_attack_reaches_the_player() {
+    let mut game = game_with_player(8, 3);
+    add_grunt(&mut game, 8, 12);
+
+    for _ in 0..7 {
+        game.step(Action::MoveEnemies);
+    }
+    assert_eq!(enemy_positions(&game), vec![Pos::new(8, 5)]);
+
+    // In reach, it stays put.
+    assert!(game.step(Action::MoveEnemies).is_empty());
+}
+
+#[test]
+fn enemy_in_reach_does_not_move() {
+    let mut game = game_with_player(8, 3);
+    let enemy = add_grunt(&mut game, 6, 3);
+
+    assert_eq!(next_step(&game, enemy, &enemy_sides()), None);
+}
+
+#[test]
+fn enemies_never_share_a_tile() {
+    let mut game = game_with_player(8, 3);
+    add_grunt(&mut game, 8, 7);
+    add_grunt(&mut game, 8, 8);
+    add_grunt(&mut game, 9, 8);
+
+    for _ in 0..6 {
+        game.step(Action::MoveEnemies);
+        let mut positions = enemy_positions(&game);
+        positions.sort_by_key(|pos| (pos.x, pos.y));
+        positions.dedup();
+        assert_eq!(positions.len(), 3);
+    }
+}
+
+#[test]
+fn enemy_walks_around_its_lethal_color() {
+    let mut game = game_with_player(8, 3);
+    let enemy = add_grunt(&mut game, 8, 8);
+    // A masked wall below the enemy, it has to go around it.
+    for x in 7..=9 {
+        game.board.set(Pos::new(x, 7), TilePaint::Primary(RoundColor::Red));
+    }
+
+    let step = next_step(&game, enemy, &enemy_sides());
+    assert!(matches!(step, Some(pos) if pos == Pos::new(7, 8) || pos == Pos::new(9, 8)));
+
+    // Secondary paint is harmless to enemies.
+    for x in 7..=9 {
+        game.board.set(Pos::new(x, 7), TilePaint::Secondary(RoundColor::Red));
+    }
+    assert_eq!(next_step(&game, enemy, &enemy_sides()), Some(Pos::new(8, 7)));
+}
+
+#[test]
+fn trapped_enemy_stays() {
+    let mut game = game_with_player(8, 3);
+    let enemy = add_grunt(&mut game, 0, 15);
+    game.board.set(Pos::new(1, 15), TilePaint::Primary(RoundColor::Red));
+    game.board.set(Pos::new(0, 14), TilePaint::Primary(RoundColor::Red));
+
+    assert_eq!(next_step(&game, enemy, &enemy_sides()), None);
+}
+
+#[test]
+fn runner_moves_twice_and_tank_every_other_turn() {
+    let mut game = game_with_player(8, 3);
+    game.roster.insert(
+        EnemyKind::Runner,
+        EnemyRules {
+            pattern: enemy_sides(),
+            movement: EnemyMovement { steps: 2, every: 1 },
+            ..Default::default()
+        },
+    );
+    game.roster.insert(
+        EnemyKind::Tank,
+        EnemyRules {
+            pattern: enemy_sides(),
+            movement: EnemyMovement { steps: 1, every: 2 },
+            health: 2,
+        },
+    );
+    game.add_enemy(EnemyKind::Runner, Pos::new(2, 12), 1);
+    game.add_enemy(EnemyKind::Tank, Pos::new(14, 12), 2);
+
+    game.turn = 1;
+    game.step(Action::MoveEnemies);
+    assert_eq!(enemy_positions(&game)[1], Pos::new(14, 12));
+    let runner = enemy_positions(&game)[0];
+    assert_eq!(runner.x.abs_diff(2) + runner.y.abs_diff(12), 2);
+
+    game.turn = 2;
+    game.step(Action::MoveEnemies);
+    let tank = enemy_positions(&game)[1];
+    assert_eq!(tank.x.abs_diff(14) + tank.y.abs_diff(12), 1);
+}
+
+#[test]
+fn tank_takes_two_hits() {
+    let mut game = game_with_player(8, 3);
+    let tank = game.add_enemy(EnemyKind::Tank, Pos::new(10, 3), 2);
+    let sides = enemy_sides();
+
+    let events = game.step(Action::PlayerAttack(sides.clone()));
+    assert!(events.contains(&Event::UnitDamaged {
+        unit: tank,
+        health: 1
+    }));
+    assert_eq!(enemy_positions(&game), vec![Pos::new(10, 3)]);
+
+    game.step(Action::PlayerAttack(sides));
+    assert!(enemy_positions(&game).is_empty());
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+
+// Puts an enemy next to the player that stays there and hits it at the end of the turn.
+fn start_next_to_enemy() -> Harness {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.spawn_enemy(TilePos { x: 8, y: 6 });
+    harness
+}
+
+fn enemy_turn(harness: &mut Harness) {
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+}
+
+fn is_invulnerable(harness: &mut Harness) -> bool {
+    let world = harness.app.world_mut();
+    world
+        .query_filtered::<(), (With<Player>, With<Invulnerable>)>()
+        .single(world)
+        .is_ok()
+}
+
+#[test]
+fn player_starts_with_every_life() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    assert_eq!(harness.player_health(), Some(PLAYER_LIVES));
+}
+
+#[test]
+fn hit_costs_a_life_and_protects_the_next_turn() {
+    let mut harness = start_next_to_enemy();
+    // The diagonal misses the enemy.
+    harness.press(KeyCode::KeyQ);
+    enemy_turn(&mut harness);
+
+    assert_eq!(harness.player_health(), Some(PLAYER_LIVES - 1));
+    assert!(is_invulnerable(&mut harness));
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    assert_eq!(harness.app_state(), ApplicationState::Gameplay);
+
+    // Same spot, same enemy, but the hit is ignored this turn.
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::ArrowDown);
+    harness.press(KeyCode::KeyQ);
+    enemy_turn(&mut harness);
+    assert_eq!(harness.player_health(), Some(PLAYER_LIVES - 1));
+
+    // Protection is over once the next turn starts.
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    assert!(!is_invulnerable(&mut harness));
+}
+
+#[test]
+fn killed_enemy_plays_its_death_before_despawning() {
+    let mut harness = start_next_to_enemy();
+
+    harness.press(KeyCode::KeyW);
+
+    // Out of the game right away, but still on screen.
+    assert!(harness.enemy_positions().is_empty());
+    let world = harness.app.world_mut();
+    let dying = world
+        .query_filtered::<Entity, With<Dying>>()
+        .iter(world)
+        .count();
+    assert_eq!(dying, 1);
+
+    harness.advance(DEATH_ANIMATION_SECONDS);
+    let world = harness.app.world_mut();
+    let dying = world
+        .query_filtered::<Entity, With<Dying>>()
+        .iter(world)
+        .count();
+    assert_eq!(dying, 0);
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+use common::{Harness, TEST_SEED, temp_data_dir};
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::high_scores::*;
+use global_game_jam_2026::input::process_keyboard;
+use global_game_jam_2026::stats::RunStats;
+
+fn entry(name: &str, score: u32) -> HighScoreEntry {
+    HighScoreEntry {
+        name: name.to_string(),
+        score,
+        turns: 1,
+        seed: 0,
+        date: String::from("2026-01-30"),
+    }
+}
+
+fn type_key(harness: &mut Harness, key_code: KeyCode, logical_key: Key) {
+    harness.app.world_mut().write_message(KeyboardInput {
+        key_code,
+        logical_key,
+        state: ButtonState::Pressed,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+    harness.update();
+}
+
+#[test]
+fn table_stays_sorted_and_capped() {
+    let mut high_scores = HighScores::default();
+    assert!(!high_scores.qualifies(0));
+
+    for score in 1..=MAX_HIGH_SCORES as u32 {
+        high_scores.insert(entry("low", score * 100));
+    }
+    assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+    assert!(!high_scores.qualifies(100));
+    assert!(high_scores.qualifies(150));
+
+    assert_eq!(high_scores.insert(entry("best", 5000)), Some(0));
+    assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+    assert_eq!(high_scores.entries[0].name, "best");
+    assert_eq!(high_scores.entries.last().unwrap().score, 200);
+    assert_eq!(high_scores.insert(entry("worst", 50)), None);
+}
+
+#[test]
+fn table_survives_a_save_and_load() {
+    let path = temp_data_dir().join("high_scores.ron");
+    assert_eq!(HighScores::load(&path), HighScores::default());
+
+    let mut high_scores = HighScores::default();
+    high_scores.insert(entry("a", 300));
+    high_scores.insert(entry("b", 700));
+    high_scores.save(&path).unwrap();
+
+    assert_eq!(HighScores::load(&path), high_scores);
+}
+
+#[test]
+fn new_record_asks_for_a_name_and_saves_it() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<RunStats>()
+        .record_attack("sides", 2);
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::EndMenu);
+    harness.update();
+    assert!(harness.app.world().contains_resource::<NameEntry>());
+
+    type_key(&mut harness, KeyCode::KeyM, Key::Character("M".into()));
+    type_key(&mut harness, KeyCode::KeyO, Key::Character("o".into()));
+    type_key(&mut harness, KeyCode::Minus, Key::Character("-".into()));
+    type_key(&mut harness, KeyCode::Enter, Key::Enter);
+    assert!(!harness.app.world().contains_resource::<NameEntry>());
+
+    let path = harness.app.world().resource::<HighScorePath>().0.clone();
+    let saved = HighScores::load(&path);
+    assert_eq!(saved.entries.len(), 1);
+    assert_eq!(saved.entries[0].name, "Mo");
+    assert_eq!(saved.entries[0].score, 300);
+    assert_eq!(saved.entries[0].seed, TEST_SEED);
+}
+
+#[test]
+fn escape_on_the_end_screen_keeps_the_record() {
+    let mut harness = Harness::new();
+    harness.app.add_systems(Update, process_keyboard);
+    harness.start_gameplay();
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<RunStats>()
+        .record_attack("sides", 2);
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::EndMenu);
+    harness.update();
+    assert!(harness.app.world().contains_resource::<NameEntry>());
+
+    // The first press only leaves the end screen, the record is saved on the way out.
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.app_state(), ApplicationState::MainMenu);
+    assert_eq!(harness.app.should_exit(), None);
+    let path = harness.app.world().resource::<HighScorePath>().0.clone();
+    assert_eq!(HighScores::load(&path).entries.len(), 1);
+
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.app.should_exit(), Some(AppExit::Success));
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::{Harness, temp_data_dir};
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::hud::AbilityHud;
+use global_game_jam_2026::input::*;
+
+#[test]
+fn binding_a_key_takes_it_from_the_other_action() {
+    let mut bindings = KeyBindings::default();
+    assert_eq!(bindings.keys(InputAction::Attack1), &[KeyCode::KeyQ]);
+
+    bindings.bind(InputAction::MoveUp, KeyCode::KeyQ);
+    assert_eq!(bindings.keys(InputAction::MoveUp), &[KeyCode::KeyQ]);
+    assert!(bindings.keys(InputAction::Attack1).is_empty());
+}
+
+#[test]
+fn missing_actions_keep_their_default_keys() {
+    let path = temp_data_dir().join("keybindings.ron");
+    assert_eq!(KeyBindings::load(&path), KeyBindings::default());
+
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "(keys: { MoveUp: [KeyI] })").unwrap();
+    let bindings = KeyBindings::load(&path);
+    assert_eq!(bindings.keys(InputAction::MoveUp), &[KeyCode::KeyI]);
+    assert_eq!(bindings.keys(InputAction::MoveDown), &[KeyCode::ArrowDown]);
+
+    std::fs::write(&path, "not bindings").unwrap();
+    assert_eq!(KeyBindings::load(&path), KeyBindings::default());
+}
+
+#[test]
+fn rebound_key_moves_the_player_and_is_saved() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.app.world_mut().resource_mut::<Rebinding>().0 = Some(InputAction::MoveUp);
+    // The key goes to the binding, not to the game.
+    harness.press(KeyCode::KeyI);
+    assert_eq!(harness.app.world().resource::<Rebinding>().0, None);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+
+    harness.press(KeyCode::ArrowUp);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    harness.press(KeyCode::KeyI);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+
+    let path = harness.app.world().resource::<KeyBindingsPath>().0.clone();
+    assert_eq!(
+        KeyBindings::load(&path).keys(InputAction::MoveUp),
+        &[KeyCode::KeyI]
+    );
+}
+
+#[test]
+fn hud_shows_the_bound_attack_keys() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    harness.app.world_mut().resource_mut::<Rebinding>().0 = Some(InputAction::Attack1);
+    harness.press(KeyCode::KeyA);
+
+    let world = harness.app.world_mut();
+    let text = world
+        .query_filtered::<&Text, With<AbilityHud>>()
+        .single(world)
+        .expect("no ability hud")
+        .0
+        .clone();
+    assert!(text.contains("A diagonal"));
+    assert!(!text.contains("Q diagonal"));
+    assert!(text.contains("W sides"));
+}
+
+#[test]
+fn gamepad_moves_and_attacks() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.press_button(GamepadButton::DPadUp);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+    harness.press_button(GamepadButton::RightTrigger);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+
+    harness.press_button(GamepadButton::North);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+}
+
+#[test]
+fn held_stick_moves_one_tile() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.tilt_stick(Vec2::new(0.1, 0.9));
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+    harness.press(KeyCode::Space);
+    harness.press(KeyCode::KeyW);
+
+    // Still held when the next move starts, nothing happens until it goes back to the middle.
+    assert!(harness.run_until(TurnState::MovePlayer, 200));
+    harness.advance(MOVE_DELAY_SECONDS);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+
+    harness.tilt_stick(Vec2::ZERO);
+    harness.tilt_stick(Vec2::new(-0.9, 0.0));
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 7, y: 4 }));
+}
+
+#[test]
+fn stick_picks_the_axis_pushed_further() {
+    assert_eq!(stick_action(Vec2::new(0.2, 0.3)), None);
+    assert_eq!(stick_action(Vec2::new(0.8, -0.4)), Some(InputAction::MoveRight));
+    assert_eq!(stick_action(Vec2::new(0.3, -0.7)), Some(InputAction::MoveDown));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::save_game::{SaveGame, SavePath};
+
+fn time_paused(harness: &Harness) -> bool {
+    harness.app.world().resource::<Time<Virtual>>().is_paused()
+}
+
+#[test]
+fn escape_pauses_and_freezes_the_turn() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.press(KeyCode::KeyW);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.pause_state(), Some(PauseState::Paused));
+    assert!(time_paused(&harness));
+
+    // Nothing moves on however long the game stays paused.
+    let enemies = harness.enemy_positions();
+    harness.advance(MOVE_DELAY_SECONDS * 4.0);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+    assert_eq!(harness.enemy_positions(), enemies);
+
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.pause_state(), Some(PauseState::Running));
+    assert!(!time_paused(&harness));
+    harness.advance(MOVE_DELAY_SECONDS);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackEnemy));
+}
+
+#[test]
+fn paused_player_cannot_move() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.press(KeyCode::Escape);
+    harness.press(KeyCode::ArrowUp);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+
+    harness.click(ButtonAction::Resume);
+    assert_eq!(harness.pause_state(), Some(PauseState::Running));
+    harness.press(KeyCode::ArrowUp);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+}
+
+#[test]
+fn settings_go_back_to_the_pause_menu() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.press(KeyCode::Escape);
+
+    harness.click(ButtonAction::Settings);
+    assert_eq!(harness.pause_state(), Some(PauseState::Settings));
+    assert!(time_paused(&harness));
+
+    harness.click(ButtonAction::Pause);
+    assert_eq!(harness.pause_state(), Some(PauseState::Paused));
+}
+
+#[test]
+fn quit_to_menu_keeps_the_save_and_unpauses() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.press(KeyCode::Escape);
+
+    harness.click(ButtonAction::QuitToMenu);
+    assert_eq!(
+        harness.pause_state(),
+        Some(PauseState::ConfirmQuit(QuitTarget::Menu))
+    );
+    harness.click(ButtonAction::ConfirmQuit);
+    assert_eq!(harness.app_state(), ApplicationState::MainMenu);
+    assert_eq!(harness.pause_state(), None);
+    assert!(!time_paused(&harness));
+    assert!(harness.saved_game().is_some());
+}
+
+#[test]
+fn save_and_quit_exits_the_app() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.app.should_exit(), None);
+
+    harness.click(ButtonAction::SaveAndQuit);
+    assert_eq!(harness.app.should_exit(), None);
+    // Saved right before quitting, not only by the last autosave.
+    let path = harness.app.world().resource::<SavePath>().0.clone();
+    SaveGame::delete(&path).unwrap();
+    harness.click(ButtonAction::ConfirmQuit);
+    assert_eq!(harness.app.should_exit(), Some(AppExit::Success));
+    let save = harness.saved_game().expect("run wasn't saved");
+    assert_eq!(save.phase, TurnState::MovePlayer);
+}
+
+#[test]
+fn declining_to_quit_keeps_the_run_paused() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.press(KeyCode::Escape);
+
+    harness.click(ButtonAction::SaveAndQuit);
+    assert_eq!(
+        harness.pause_state(),
+        Some(PauseState::ConfirmQuit(QuitTarget::Desktop))
+    );
+    harness.click(ButtonAction::Pause);
+    assert_eq!(harness.pause_state(), Some(PauseState::Paused));
+    assert!(time_paused(&harness));
+
+    // Escape says no as well.
+    harness.click(ButtonAction::QuitToMenu);
+    harness.press(KeyCode::Escape);
+    assert_eq!(harness.pause_state(), Some(PauseState::Paused));
+    assert_eq!(harness.app_state(), ApplicationState::Gameplay);
+    assert_eq!(harness.app.should_exit(), None);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::playzone::*;
+use global_game_jam_2026::rules::{Pos, Terrain};
+
+fn playzone(harness: &Harness) -> Playzone {
+    harness.app.world().resource::<Playzone>().clone()
+}
+
+#[test]
+fn layout_comes_from_the_tiled_map() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    let playzone = playzone(&harness);
+
+    assert_eq!((playzone.width, playzone.height), (16, 16));
+    assert_eq!(playzone.player_start, Pos::new(8, 3));
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(playzone.terrain_at(Pos::new(1, 8)), Terrain::Wall);
+    assert_eq!(playzone.terrain_at(Pos::new(14, 8)), Terrain::Pit);
+    assert_eq!(playzone.terrain_at(Pos::new(8, 8)), Terrain::Floor);
+
+    // The first wave only opens the front zone.
+    let tiles = playzone.spawn_tiles(0);
+    assert_eq!(tiles.len(), 32);
+    assert!(tiles.iter().all(|pos| (13..15).contains(&pos.y)));
+}
+
+#[test]
+fn enemies_spawn_in_the_open_zones() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    let tiles = playzone(&harness).spawn_tiles(0);
+
+    for enemy in harness.enemy_positions() {
+        assert!(tiles.contains(&Pos::new(enemy.x, enemy.y)));
+    }
+}
+
+#[test]
+fn later_waves_open_more_zones_but_never_terrain_tiles() {
+    let playzone = Playzone {
+        terrain: vec![(Pos::new(0, 5), Terrain::Pit)],
+        spawn_zones: vec![
+            SpawnZone {
+                from_wave: 0,
+                tiles: vec![Pos::new(0, 5), Pos::new(1, 5)],
+            },
+            SpawnZone {
+                from_wave: 2,
+                tiles: vec![Pos::new(1, 5), Pos::new(1, 6)],
+            },
+        ],
+        ..Playzone::default()
+    };
+
+    assert_eq!(playzone.spawn_tiles(0), vec![Pos::new(1, 5)]);
+    assert_eq!(playzone.spawn_tiles(1), vec![Pos::new(1, 5)]);
+    assert_eq!(
+        playzone.spawn_tiles(2),
+        vec![Pos::new(1, 5), Pos::new(1, 6)]
+    );
+}
+
+#[test]
+fn runs_wait_for_the_map() {
+    let mut harness = Harness::new();
+    let ready = |harness: &mut Harness| {
+        harness
+            .app
+            .world_mut()
+            .run_system_once(|map: PlayzoneMap| map.is_ready())
+            .unwrap()
+    };
+    assert!(ready(&mut harness));
+
+    // A map that's still on its way.
+    harness
+        .app
+        .insert_resource(PlayzoneHandle(Handle::default()));
+    assert!(!ready(&mut harness));
+}
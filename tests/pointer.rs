@@ -0,0 +1,76 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+
+#[test]
+fn clicking_a_tile_next_to_the_player_moves_there() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    // Too far away to step on.
+    harness.click_tile(TilePos { x: 10, y: 3 });
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+
+    harness.click_tile(TilePos { x: 9, y: 3 });
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 9, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+
+    harness.click_tile(TilePos { x: 9, y: 4 });
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 9, y: 4 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+}
+
+#[test]
+fn clicking_a_button_does_not_move_the_player() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    // Resume sits over a tile next to the player.
+    harness.press(KeyCode::Escape);
+    harness.click_pointer(TilePos { x: 9, y: 3 }, Some(ButtonAction::Resume));
+    assert_eq!(harness.pause_state(), Some(PauseState::Running));
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+
+    // Same for any button the pointer is on while playing.
+    let button = harness.app.world_mut().spawn(Interaction::Hovered).id();
+    harness.click_pointer(TilePos { x: 9, y: 3 }, None);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+
+    harness.app.world_mut().entity_mut(button).despawn();
+    harness.click_pointer(TilePos { x: 9, y: 3 }, None);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 9, y: 3 }));
+}
+
+#[test]
+fn hovering_previews_the_ready_attacks() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    // Not next to the player, nothing to preview.
+    harness.hover(Some(TilePos { x: 2, y: 2 }));
+    assert!(harness.attack_preview().is_empty());
+
+    // From the tile the player would step on.
+    harness.hover(Some(TilePos { x: 8, y: 4 }));
+    let preview = harness.attack_preview();
+    assert!(preview.contains(&(0, TilePos { x: 9, y: 5 })));
+    assert!(preview.contains(&(1, TilePos { x: 8, y: 9 })));
+    assert!(preview.contains(&(2, TilePos { x: 7, y: 4 })));
+    // The ultimate has no charges yet.
+    assert!(!preview.iter().any(|(slot, _)| *slot == 3));
+
+    harness.hover(None);
+    assert!(harness.attack_preview().is_empty());
+}
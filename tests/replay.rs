@@ -0,0 +1,106 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::{Harness, TEST_SEED};
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::input::InputAction;
+use global_game_jam_2026::replay::*;
+
+fn leave_run(harness: &mut Harness) {
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::MainMenu);
+    harness.update();
+}
+
+// Moves and attacks for two turns, the second move bumps into nothing.
+fn play_two_turns(harness: &mut Harness) {
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.press(KeyCode::KeyW);
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowLeft);
+    harness.press(KeyCode::ArrowLeft);
+    harness.press(KeyCode::KeyQ);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+}
+
+#[test]
+fn run_is_recorded_when_it_is_left() {
+    let mut harness = Harness::new();
+    play_two_turns(&mut harness);
+    leave_run(&mut harness);
+
+    let path = harness.app.world().resource::<ReplayPath>().0.clone();
+    let replay = Replay::load(&path).expect("replay wasn't saved");
+    assert_eq!(replay.seed, TEST_SEED);
+    assert_eq!(
+        replay.actions,
+        vec![
+            InputAction::MoveUp,
+            InputAction::EndMove,
+            InputAction::Attack2,
+            InputAction::MoveLeft,
+            InputAction::MoveLeft,
+            InputAction::Attack1,
+        ]
+    );
+}
+
+#[test]
+fn playback_plays_the_same_run() {
+    let mut recorded = Harness::new();
+    play_two_turns(&mut recorded);
+    let player = recorded.player_pos();
+    let enemies = recorded.enemy_positions();
+    leave_run(&mut recorded);
+    let path = recorded.app.world().resource::<ReplayPath>().0.clone();
+    let replay = Replay::load(&path).unwrap();
+
+    // Another seed, the replay brings its own.
+    let mut watched = Harness::with_seed(1);
+    watched.app.insert_resource(ReplayPlayback::new(replay));
+    watched.start_gameplay();
+    for _ in 0..500 {
+        if watched.app.world().resource::<ReplayPlayback>().is_finished() {
+            break;
+        }
+        watched.update();
+    }
+    assert!(watched.app.world().resource::<ReplayPlayback>().is_finished());
+
+    assert_eq!(watched.turn_state(), Some(TurnState::MoveEnemy));
+    assert_eq!(watched.player_pos(), player);
+    assert_eq!(watched.enemy_positions(), enemies);
+    // Watching doesn't save over the player's run.
+    assert!(watched.saved_game().is_none());
+}
+
+#[test]
+fn paused_playback_only_moves_on_a_step() {
+    let mut harness = Harness::new();
+    let mut playback = ReplayPlayback::new(Replay {
+        seed: TEST_SEED,
+        actions: vec![InputAction::MoveUp, InputAction::MoveUp],
+    });
+    playback.paused = true;
+    harness.app.insert_resource(playback);
+    harness.start_gameplay();
+
+    harness.advance(MOVE_DELAY_SECONDS * 2.0);
+    assert_eq!(harness.app.world().resource::<ReplayPlayback>().next, 0);
+
+    harness.app.world_mut().resource_mut::<ReplayPlayback>().step = true;
+    harness.advance(MOVE_DELAY_SECONDS * 2.0);
+    assert_eq!(harness.app.world().resource::<ReplayPlayback>().next, 1);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::{Harness, temp_data_dir};
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::save_game::*;
+use global_game_jam_2026::stats::RunStats;
+
+fn board(harness: &mut Harness) -> Vec<Option<Color>> {
+    let mut colors = Vec::new();
+    for x in 0..TILEMAP_SIDE_LENGHT {
+        for y in 0..TILEMAP_SIDE_LENGHT {
+            colors.push(harness.tile_color(TilePos { x, y }));
+        }
+    }
+    colors
+}
+
+// Plays the rest of the turn and the moves of the next one.
+fn play_on(harness: &mut Harness) {
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowLeft);
+}
+
+#[test]
+fn continued_run_matches_the_saved_one() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.press(KeyCode::KeyW);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+
+    let save = harness.saved_game().expect("run wasn't saved");
+    assert_eq!(save.phase, TurnState::MoveEnemy);
+    assert_eq!(save.player.pos.y, 4);
+    assert!(!save.tiles.is_empty());
+
+    let mut continued = Harness::with_seed(1);
+    continued.continue_run(save);
+    assert_eq!(continued.player_pos(), harness.player_pos());
+    assert_eq!(continued.enemy_positions(), harness.enemy_positions());
+    assert_eq!(continued.round_color_index(), harness.round_color_index());
+    assert_eq!(board(&mut continued), board(&mut harness));
+    assert_eq!(continued.abilities(), harness.abilities());
+    assert_eq!(
+        continued.app.world().resource::<RunStats>(),
+        harness.app.world().resource::<RunStats>()
+    );
+    assert_eq!(
+        continued.app.world().resource::<GameRng>().seed(),
+        harness.app.world().resource::<GameRng>().seed()
+    );
+
+    // Same random stream from here on, so the runs stay the same.
+    play_on(&mut harness);
+    play_on(&mut continued);
+    assert_eq!(continued.round_color_index(), harness.round_color_index());
+    assert_eq!(continued.enemy_positions(), harness.enemy_positions());
+    assert_eq!(continued.player_pos(), harness.player_pos());
+    assert_eq!(board(&mut continued), board(&mut harness));
+}
+
+#[test]
+fn finished_run_deletes_the_save() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    assert!(harness.saved_game().is_some());
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::EndMenu);
+    harness.update();
+    assert!(harness.saved_game().is_none());
+    assert!(!harness.app.world().resource::<SavePath>().exists());
+}
+
+#[test]
+fn broken_save_is_ignored() {
+    let path = temp_data_dir().join("save.ron");
+    assert_eq!(SaveGame::load(&path), None);
+
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "not a save").unwrap();
+    assert_eq!(SaveGame::load(&path), None);
+}
+
+#[test]
+fn quitting_halfway_through_the_move_keeps_the_moves_left() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    assert_eq!(harness.app.world().resource::<ActionPoints>().0, 1);
+
+    harness.press(KeyCode::Escape);
+    harness.click(ButtonAction::SaveAndQuit);
+    harness.click(ButtonAction::ConfirmQuit);
+    let save = harness.saved_game().expect("run wasn't saved");
+    assert_eq!(save.action_points, 1);
+
+    let mut continued = Harness::with_seed(1);
+    continued.continue_run(save);
+    assert_eq!(continued.app.world().resource::<ActionPoints>().0, 1);
+    continued.advance(MOVE_DELAY_SECONDS);
+    continued.press(KeyCode::ArrowUp);
+    assert_eq!(continued.player_pos(), Some(TilePos { x: 8, y: 5 }));
+    assert_eq!(continued.turn_state(), Some(TurnState::AttackPlayer));
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::playzone::Playzone;
+use global_game_jam_2026::rules::{Pos, RoundColor, TilePaint};
+use global_game_jam_2026::special_tiles::{POOL_KILLS, UnitEnteredTile};
+use global_game_jam_2026::stats::RunStats;
+use global_game_jam_2026::troop_utilities::paint_color;
+
+fn set_round_color(harness: &mut Harness, index: i32) {
+    let world = harness.app.world_mut();
+    world
+        .query::<&mut RoundColorState>()
+        .single_mut(world)
+        .expect("no round color state")
+        .index = index;
+}
+
+// Walking in goes through the rules, this puts the unit there and says it walked in.
+fn enter(harness: &mut Harness, entity: Entity, tile: TilePos) {
+    let world = harness.app.world_mut();
+    *world.get_mut::<TilePos>(entity).unwrap() = tile;
+    world.write_message(UnitEnteredTile { entity, tile });
+    harness.update();
+}
+
+fn paint(harness: &mut Harness, tile: TilePos, paint: TilePaint) {
+    let world = harness.app.world_mut();
+    let storage = world
+        .query_filtered::<&TileStorage, With<PlayZoneTilemap>>()
+        .single(world)
+        .expect("no tilemap");
+    let tile_entity = storage.get(&tile).expect("no tile");
+    world.get_mut::<TileColor>(tile_entity).unwrap().0 = paint_color(paint);
+}
+
+fn player(harness: &mut Harness) -> Entity {
+    let world = harness.app.world_mut();
+    world
+        .query_filtered::<Entity, With<Player>>()
+        .single(world)
+        .expect("no player")
+}
+
+#[test]
+fn portals_are_paired_by_name() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    let playzone = harness.app.world().resource::<Playzone>().clone();
+
+    let mut portals = playzone.portals();
+    portals.sort_by_key(|(from, _)| from.x);
+    assert_eq!(
+        portals,
+        vec![
+            (Pos::new(0, 1), Pos::new(15, 1)),
+            (Pos::new(15, 1), Pos::new(0, 1)),
+        ]
+    );
+}
+
+#[test]
+fn stepping_on_a_portal_comes_out_of_its_pair() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.set_player_pos(TilePos { x: 1, y: 1 });
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.press(KeyCode::ArrowLeft);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 15, y: 1 }));
+
+    // Coming out doesn't count as stepping in, so the pair doesn't send the player back.
+    harness.update();
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 15, y: 1 }));
+}
+
+#[test]
+fn portals_only_lead_where_the_unit_could_walk() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    let portal = TilePos { x: 0, y: 1 };
+    let exit = TilePos { x: 15, y: 1 };
+
+    // Someone standing on the exit keeps the player on the portal.
+    harness.spawn_enemy(exit);
+    harness.set_player_pos(TilePos { x: 1, y: 1 });
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowLeft);
+    assert_eq!(harness.player_pos(), Some(portal));
+
+    // Enemies don't come out on the color they die on.
+    harness.clear_enemies();
+    harness.set_player_pos(TilePos { x: 8, y: 3 });
+    set_round_color(&mut harness, 0);
+    paint(&mut harness, exit, TilePaint::Primary(RoundColor::Red));
+    let enemy = harness.app.world_mut().spawn((Troop, Enemy, portal)).id();
+    enter(&mut harness, enemy, portal);
+    assert_eq!(harness.enemy_positions(), vec![portal]);
+}
+
+#[test]
+fn color_pools_drown_enemies_of_their_color_only() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    let red_pool = TilePos { x: 0, y: 0 };
+
+    // Blue is masked, the red pool is harmless.
+    set_round_color(&mut harness, 2);
+    let enemy = harness.app.world_mut().spawn((Troop, Enemy, red_pool)).id();
+    enter(&mut harness, enemy, red_pool);
+    assert_eq!(harness.enemy_positions(), vec![red_pool]);
+
+    set_round_color(&mut harness, 0);
+    let charges = harness.abilities().charges;
+    enter(&mut harness, enemy, red_pool);
+    assert!(harness.enemy_positions().is_empty());
+
+    // Drowning counts like a kill of the player's attacks.
+    assert_eq!(harness.abilities().charges, charges + 1);
+    let stats = harness.app.world().resource::<RunStats>();
+    assert_eq!(stats.kills.get(POOL_KILLS), Some(&1));
+
+    // The player wears the complement and wades through.
+    let player = player(&mut harness);
+    enter(&mut harness, player, red_pool);
+    assert_eq!(harness.player_pos(), Some(red_pool));
+}
+
+#[test]
+fn mask_altar_rerolls_the_color_once() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    let color = harness.round_color_index();
+    harness.set_player_pos(TilePos { x: 3, y: 1 });
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    harness.press(KeyCode::ArrowDown);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 3, y: 0 }));
+    let rerolled = harness.round_color_index();
+    assert_ne!(rerolled, color);
+
+    let player = player(&mut harness);
+    enter(&mut harness, player, TilePos { x: 3, y: 0 });
+    assert_eq!(harness.round_color_index(), rerolled);
+
+    // The used altar goes into the save so continuing doesn't give it back.
+    harness.press(KeyCode::Space);
+    let save = harness.saved_game().expect("no save");
+    assert_eq!(save.altars_used, vec![Pos::new(3, 0)]);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::stats::*;
+
+fn stats(harness: &Harness) -> RunStats {
+    harness.app.world().resource::<RunStats>().clone()
+}
+
+#[test]
+fn combo_kills_are_worth_more() {
+    let mut stats = RunStats::default();
+
+    stats.record_attack("sides", 1);
+    assert_eq!(stats.score, SCORE_PER_KILL);
+
+    stats.record_attack("around", 3);
+    assert_eq!(stats.score, SCORE_PER_KILL * 7);
+    assert_eq!(stats.combo, 3);
+
+    stats.record_attack("sides", 0);
+    assert_eq!(stats.combo, 0);
+    assert_eq!(stats.best_combo, 3);
+    assert_eq!(stats.kills.get("sides"), Some(&1));
+    assert_eq!(stats.total_kills(), 4);
+}
+
+#[test]
+fn run_tracks_kills_turns_and_colors() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.spawn_enemy(TilePos { x: 10, y: 4 });
+    harness.spawn_enemy(TilePos { x: 8, y: 7 });
+
+    harness.press(KeyCode::KeyW);
+
+    let after_attack = stats(&harness);
+    assert_eq!(after_attack.kills.get("sides"), Some(&2));
+    assert_eq!(after_attack.best_combo, 2);
+    assert_eq!(after_attack.score, SCORE_PER_KILL * 3);
+    assert_eq!(after_attack.colors_seen.iter().sum::<u32>(), 1);
+
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+
+    let next_turn = stats(&harness);
+    assert_eq!(next_turn.turns_survived, 1);
+    assert_eq!(next_turn.score, SCORE_PER_KILL * 3 + SCORE_PER_TURN);
+    assert_eq!(next_turn.colors_seen.iter().sum::<u32>(), 2);
+}
+
+#[test]
+fn stats_reset_when_a_new_run_starts() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<RunStats>()
+        .record_attack("sides", 2);
+
+    harness
+        .app
+        .world_mut()
+        .resource_mut::<NextState<ApplicationState>>()
+        .set(ApplicationState::EndMenu);
+    harness.update();
+    // Still there for the end screen.
+    assert_eq!(stats(&harness).total_kills(), 2);
+
+    harness.start_gameplay();
+    assert_eq!(stats(&harness).total_kills(), 0);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::rules::{Pos, RoundColor, TilePaint};
+use global_game_jam_2026::telegraph::*;
+use global_game_jam_2026::troop_utilities::paint_color;
+
+fn intents(harness: &Harness) -> Vec<Pos> {
+    harness.app.world().resource::<EnemyIntents>().0.clone()
+}
+
+fn marker_count(harness: &mut Harness) -> usize {
+    let world = harness.app.world_mut();
+    world.query::<&IntentMarker>().iter(world).count()
+}
+
+#[test]
+fn windup_shows_the_tiles_the_enemies_hit() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+
+    // Shown during the player turn already, from where the enemy ends up after its move.
+    harness.spawn_enemy(TilePos { x: 2, y: 12 });
+    harness.update();
+    assert!(!intents(&harness).is_empty());
+
+    harness.press(KeyCode::KeyQ);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+    let planned = intents(&harness);
+    // Not even for the frame the enemy moved in.
+    while harness.turn_state() == Some(TurnState::MoveEnemy) {
+        harness.update();
+        assert_eq!(intents(&harness), planned);
+    }
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackEnemy));
+    let enemy = harness.enemy_positions()[0];
+    assert_ne!(enemy, TilePos { x: 2, y: 12 });
+    let telegraphed = intents(&harness);
+    assert_eq!(telegraphed, planned);
+    assert!(telegraphed.contains(&Pos::new(enemy.x, enemy.y)));
+    assert_eq!(marker_count(&mut harness), telegraphed.len());
+
+    // Every telegraphed tile is painted once the windup is over, and the markers go away.
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    let round_color = RoundColor::from_index(harness.round_color_index());
+    let enemy_color = paint_color(TilePaint::Secondary(round_color));
+    for pos in telegraphed {
+        assert_eq!(
+            harness.tile_color(TilePos { x: pos.x, y: pos.y }),
+            Some(enemy_color)
+        );
+    }
+    assert!(intents(&harness).is_empty());
+    assert_eq!(marker_count(&mut harness), 0);
+}
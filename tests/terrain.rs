@@ -0,0 +1,111 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::enemy_ai::next_step;
+use global_game_jam_2026::playzone::Playzone;
+use global_game_jam_2026::rules::*;
+
+fn sides(range: i32) -> AttackPattern {
+    AttackPattern {
+        rays: vec![(1, 0), (-1, 0)],
+        range,
+        ..Default::default()
+    }
+}
+
+fn game_with_terrain(terrain: &[(u32, u32, Terrain)]) -> Game {
+    let mut board = Board::default();
+    for (x, y, terrain) in terrain {
+        board.set_terrain(Pos::new(*x, *y), *terrain);
+    }
+    Game::new(board, RoundColor::Red)
+}
+
+#[test]
+fn walls_stop_attack_rays_but_water_and_pits_dont() {
+    let game = game_with_terrain(&[(6, 3, Terrain::Wall), (10, 3, Terrain::Water)]);
+
+    let tiles = pattern_tiles(&sides(3), Pos::new(8, 3), &game.board);
+    assert_eq!(
+        tiles,
+        vec![
+            Pos::new(8, 3),
+            Pos::new(9, 3),
+            Pos::new(7, 3),
+            Pos::new(10, 3),
+            Pos::new(11, 3),
+        ]
+    );
+}
+
+#[test]
+fn player_cannot_step_off_the_floor() {
+    let mut game = game_with_terrain(&[(9, 3, Terrain::Pit), (8, 4, Terrain::Wall)]);
+    game.add_unit(Side::Player, Pos::new(8, 3));
+
+    assert!(game.step(Action::MovePlayer(MoveDirection::Right)).is_empty());
+    assert!(game.step(Action::MovePlayer(MoveDirection::Up)).is_empty());
+    assert_eq!(game.step(Action::MovePlayer(MoveDirection::Left)).len(), 1);
+}
+
+#[test]
+fn player_cannot_step_onto_an_enemy() {
+    let mut game = game_with_terrain(&[]);
+    game.add_unit(Side::Player, Pos::new(8, 3));
+    game.add_enemy(EnemyKind::Grunt, Pos::new(8, 4), 1);
+
+    assert!(game.step(Action::MovePlayer(MoveDirection::Up)).is_empty());
+    assert_eq!(game.player().map(|player| player.pos), Some(Pos::new(8, 3)));
+    assert_eq!(game.step(Action::MovePlayer(MoveDirection::Right)).len(), 1);
+}
+
+#[test]
+fn enemies_path_around_water_and_never_spawn_on_it() {
+    let mut game = game_with_terrain(&[
+        (7, 7, Terrain::Water),
+        (8, 7, Terrain::Water),
+        (9, 7, Terrain::Water),
+    ]);
+    game.roster.insert(
+        EnemyKind::Grunt,
+        EnemyRules {
+            pattern: sides(2),
+            ..Default::default()
+        },
+    );
+    game.add_unit(Side::Player, Pos::new(8, 3));
+    let enemy = game.add_enemy(EnemyKind::Grunt, Pos::new(8, 8), 1);
+
+    let step = next_step(&game, enemy, &sides(2));
+    assert!(matches!(step, Some(pos) if pos == Pos::new(7, 8) || pos == Pos::new(9, 8)));
+    assert!(
+        game.step(Action::SpawnEnemy(Pos::new(8, 7), EnemyKind::Grunt))
+            .is_empty()
+    );
+}
+
+#[test]
+fn bumping_into_terrain_costs_no_action_points() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    let playzone = harness.app.world().resource::<Playzone>().clone();
+    assert_eq!(playzone.terrain_at(Pos::new(13, 8)), Terrain::Water);
+
+    harness.set_player_pos(TilePos { x: 12, y: 8 });
+    harness.advance(MOVE_DELAY_SECONDS);
+    let action_points = harness.app.world().resource::<ActionPoints>().0;
+    harness.press(KeyCode::ArrowRight);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 12, y: 8 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+    assert_eq!(
+        harness.app.world().resource::<ActionPoints>().0,
+        action_points
+    );
+}
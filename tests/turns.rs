@@ -0,0 +1,309 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::enemy_kinds::*;
+use global_game_jam_2026::rules::{EnemyKind, RoundColor, TilePaint};
+use global_game_jam_2026::troop_utilities::paint_color;
+
+fn masked_color(harness: &mut Harness) -> Color {
+    let round_color = RoundColor::from_index(harness.round_color_index());
+    paint_color(TilePaint::Primary(round_color))
+}
+
+fn player_color(harness: &mut Harness) -> Color {
+    let round_color = RoundColor::from_index(harness.round_color_index());
+    paint_color(TilePaint::Secondary(round_color))
+}
+
+fn action_points(harness: &Harness) -> u32 {
+    harness.app.world().resource::<ActionPoints>().0
+}
+
+// Starts a game with only the player on the board, ready to move.
+fn start_without_enemies() -> Harness {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness
+}
+
+#[test]
+fn starts_in_main_menu_without_turns() {
+    let harness = Harness::new();
+
+    assert_eq!(harness.app_state(), ApplicationState::MainMenu);
+    assert_eq!(harness.turn_state(), None);
+}
+
+#[test]
+fn entering_gameplay_runs_the_setup_phases() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert!((0..3).contains(&harness.round_color_index()));
+
+    // EnemySpawn puts one enemy on the top rows.
+    let enemies = harness.enemy_positions();
+    assert_eq!(enemies.len(), 1);
+    assert!(enemies[0].x < TILEMAP_SIDE_LENGHT);
+    assert!((13..15).contains(&enemies[0].y));
+
+    // Nothing is painted yet.
+    for x in 0..TILEMAP_SIDE_LENGHT {
+        for y in 0..TILEMAP_SIDE_LENGHT {
+            assert_eq!(harness.tile_color(TilePos { x, y }), Some(Color::WHITE));
+        }
+    }
+}
+
+#[test]
+fn spawned_enemies_get_a_kind_and_its_health() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    let world = harness.app.world_mut();
+    let (kind, health, sprite) = world
+        .query_filtered::<(&Kind, &Health, &Sprite), With<Enemy>>()
+        .single(world)
+        .expect("no spawned enemy");
+    let expected = if kind.0 == EnemyKind::Tank { 2 } else { 1 };
+    assert_eq!(health.0, expected);
+    assert!(sprite.custom_size.is_some());
+    let (kind, frame) = (
+        kind.0,
+        sprite.texture_atlas.as_ref().map(|atlas| atlas.index),
+    );
+
+    // Every kind animates through frames of its own, so the sprite tells them apart.
+    let handle = world.resource::<EnemyKindsHandle>().0.clone();
+    let enemy_kinds = world
+        .resource::<Assets<EnemyKinds>>()
+        .get(&handle)
+        .expect("enemy kinds not loaded");
+    let archetype = enemy_kinds.get(kind).unwrap();
+    assert!(frame.is_some_and(|frame| (archetype.first..=archetype.last).contains(&frame)));
+    for (kind, archetype) in enemy_kinds.kinds.iter() {
+        for (other_kind, other) in enemy_kinds.kinds.iter() {
+            let shared = archetype.sprite == other.sprite
+                && archetype.first <= other.last
+                && other.first <= archetype.last;
+            assert!(
+                kind == other_kind || !shared,
+                "{:?} looks like {:?}",
+                kind,
+                other_kind
+            );
+        }
+    }
+}
+
+#[test]
+fn player_cannot_move_before_the_delay() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    harness.press(KeyCode::ArrowUp);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+}
+
+#[test]
+fn player_moves_until_the_action_points_run_out() {
+    let mut harness = start_without_enemies();
+    assert_eq!(action_points(&harness), 2);
+
+    harness.press(KeyCode::ArrowUp);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+    assert_eq!(action_points(&harness), 1);
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+
+    harness.press(KeyCode::ArrowRight);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 9, y: 4 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+}
+
+#[test]
+fn end_move_attacks_from_where_the_player_is() {
+    let mut harness = start_without_enemies();
+
+    harness.press(KeyCode::Space);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+}
+
+#[test]
+fn moving_into_the_edge_costs_nothing() {
+    let mut harness = start_without_enemies();
+    harness.set_player_pos(TilePos { x: 0, y: 3 });
+
+    harness.press(KeyCode::ArrowLeft);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 0, y: 3 }));
+    assert_eq!(action_points(&harness), 2);
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+}
+
+#[test]
+fn sides_attack_paints_masked_color_and_kills_enemies() {
+    let mut harness = start_without_enemies();
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.spawn_enemy(TilePos { x: 10, y: 4 });
+    harness.spawn_enemy(TilePos { x: 9, y: 5 });
+
+    harness.press(KeyCode::KeyW);
+
+    let masked = masked_color(&mut harness);
+    assert_eq!(harness.tile_color(TilePos { x: 8, y: 4 }), Some(masked));
+    assert_eq!(harness.tile_color(TilePos { x: 13, y: 4 }), Some(masked));
+    assert_eq!(harness.tile_color(TilePos { x: 8, y: 0 }), Some(masked));
+    assert_eq!(harness.tile_color(TilePos { x: 9, y: 5 }), Some(Color::WHITE));
+    // Only the enemy on the painted row dies.
+    assert_eq!(harness.enemy_positions(), vec![TilePos { x: 9, y: 5 }]);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+}
+
+#[test]
+fn diagonal_attack_only_paints_diagonals() {
+    let mut harness = start_without_enemies();
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+
+    harness.press(KeyCode::KeyQ);
+
+    let masked = masked_color(&mut harness);
+    assert_eq!(harness.tile_color(TilePos { x: 9, y: 5 }), Some(masked));
+    assert_eq!(harness.tile_color(TilePos { x: 4, y: 0 }), Some(masked));
+    assert_eq!(harness.tile_color(TilePos { x: 9, y: 4 }), Some(Color::WHITE));
+}
+
+#[test]
+fn player_waits_for_an_attack_key() {
+    let mut harness = start_without_enemies();
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+
+    harness.press(KeyCode::ArrowUp);
+    harness.advance(1.0);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+}
+
+fn distance(a: TilePos, b: TilePos) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+#[test]
+fn enemies_step_toward_the_player_after_the_delay() {
+    let mut harness = start_without_enemies();
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.spawn_enemy(TilePos { x: 2, y: 12 });
+    harness.spawn_enemy(TilePos { x: 5, y: 0 });
+    harness.press(KeyCode::KeyQ);
+
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+    assert_eq!(
+        harness.enemy_positions(),
+        vec![TilePos { x: 2, y: 12 }, TilePos { x: 5, y: 0 }]
+    );
+
+    harness.advance(MOVE_DELAY_SECONDS);
+
+    let player = TilePos { x: 8, y: 4 };
+    let enemies = harness.enemy_positions();
+    assert_eq!(enemies.len(), 2);
+    assert_eq!(distance(enemies[0], player), 13);
+    assert_eq!(distance(enemies[1], player), 6);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackEnemy));
+}
+
+#[test]
+fn enemy_attack_winds_up_paints_and_starts_a_new_turn() {
+    let mut harness = start_without_enemies();
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.spawn_enemy(TilePos { x: 2, y: 12 });
+    harness.press(KeyCode::KeyQ);
+    harness.advance(MOVE_DELAY_SECONDS);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackEnemy));
+    let enemy = harness.enemy_positions()[0];
+
+    // Nothing happens during the windup.
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS * 0.5);
+    assert_eq!(harness.tile_color(enemy), Some(Color::WHITE));
+
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS * 0.5);
+    let enemy_color = player_color(&mut harness);
+    for pos in [
+        enemy,
+        TilePos { x: enemy.x - 2, ..enemy },
+        TilePos { x: enemy.x + 2, ..enemy },
+        TilePos { y: enemy.y - 2, ..enemy },
+        TilePos { y: enemy.y + 2, ..enemy },
+    ] {
+        assert_eq!(harness.tile_color(pos), Some(enemy_color));
+    }
+    assert_eq!(
+        harness.tile_color(TilePos { x: enemy.x + 3, ..enemy }),
+        Some(Color::WHITE)
+    );
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackEnemy));
+
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    assert_eq!(harness.app_state(), ApplicationState::Gameplay);
+}
+
+#[test]
+fn player_hit_on_the_last_life_ends_the_game() {
+    let mut harness = start_without_enemies();
+    harness.set_player_health(1);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    // Diagonal attacks miss it and its sides already reach the player, so it stays to attack.
+    harness.spawn_enemy(TilePos { x: 8, y: 6 });
+    harness.press(KeyCode::KeyQ);
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+
+    assert_eq!(harness.player_pos(), None);
+
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert_eq!(harness.app_state(), ApplicationState::EndMenu);
+    assert_eq!(harness.turn_state(), None);
+}
+
+#[test]
+fn same_seed_plays_the_same_turns() {
+    let mut first = Harness::with_seed(42);
+    let mut second = Harness::with_seed(42);
+
+    for _ in 0..3 {
+        first.start_gameplay();
+        second.start_gameplay();
+        assert_eq!(first.round_color_index(), second.round_color_index());
+        assert_eq!(first.enemy_positions(), second.enemy_positions());
+
+        // Restarting with a fixed seed replays the same run.
+        for harness in [&mut first, &mut second] {
+            harness
+                .app
+                .world_mut()
+                .resource_mut::<NextState<ApplicationState>>()
+                .set(ApplicationState::EndMenu);
+            harness.update();
+        }
+    }
+}
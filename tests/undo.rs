@@ -0,0 +1,106 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::undo::UndosLeft;
+
+fn turn(harness: &Harness) -> u32 {
+    harness.app.world().resource::<TurnCount>().0
+}
+
+#[test]
+fn undo_takes_back_the_moves_of_this_turn() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+
+    harness.press(KeyCode::KeyZ);
+
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+    assert_eq!(harness.app.world().resource::<ActionPoints>().0, 2);
+}
+
+#[test]
+fn undo_at_the_start_of_a_turn_rewinds_the_last_one() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    let enemies = harness.enemy_positions();
+    let first_turn = turn(&harness);
+
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    harness.press(KeyCode::KeyW);
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    assert_eq!(turn(&harness), first_turn + 1);
+
+    harness.press(KeyCode::KeyZ);
+
+    assert_eq!(turn(&harness), first_turn);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    assert_eq!(harness.enemy_positions(), enemies);
+    // The sides attack painted the whole column, the first turn started on a clean board.
+    assert_eq!(
+        harness.tile_color(TilePos { x: 8, y: 0 }),
+        Some(Color::WHITE)
+    );
+    assert_eq!(harness.turn_state(), Some(TurnState::MovePlayer));
+
+    // Only one finished turn can be taken back.
+    harness.press(KeyCode::KeyZ);
+    assert_eq!(turn(&harness), first_turn);
+}
+
+#[test]
+fn undos_run_out() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    for _ in 0..UNDOS_PER_RUN {
+        harness.advance(MOVE_DELAY_SECONDS);
+        harness.press(KeyCode::ArrowUp);
+        harness.press(KeyCode::KeyZ);
+        assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 3 }));
+    }
+    assert_eq!(harness.app.world().resource::<UndosLeft>().0, 0);
+
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::KeyZ);
+    assert_eq!(harness.player_pos(), Some(TilePos { x: 8, y: 4 }));
+    assert_eq!(harness.app.world().resource::<ActionPoints>().0, 1);
+}
+
+#[test]
+fn continued_run_keeps_the_undos_left() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::KeyZ);
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    assert_eq!(harness.turn_state(), Some(TurnState::AttackPlayer));
+
+    let save = harness.saved_game().expect("run wasn't saved");
+    assert_eq!(save.undos_left, UNDOS_PER_RUN - 1);
+
+    let mut continued = Harness::with_seed(1);
+    continued.continue_run(save);
+    assert_eq!(
+        continued.app.world().resource::<UndosLeft>().0,
+        UNDOS_PER_RUN - 1
+    );
+}
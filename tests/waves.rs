@@ -0,0 +1,163 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+
+use common::Harness;
+use global_game_jam_2026::components::*;
+use global_game_jam_2026::constants::*;
+use global_game_jam_2026::rules::{Board, EnemyKind, Event, Game, Pos, RoundColor, Side};
+use global_game_jam_2026::waves::*;
+
+fn script() -> WaveScript {
+    let wave = |count, per_turn| Wave {
+        count,
+        per_turn,
+        mix: vec![(EnemyKind::Grunt, 1)],
+    };
+    WaveScript {
+        waves: vec![wave(3, 1), wave(4, 2)],
+        intermission: 2,
+        extra_enemy_every: 10,
+    }
+}
+
+fn director(harness: &Harness) -> WaveDirector {
+    harness.app.world().resource::<WaveDirector>().clone()
+}
+
+#[test]
+fn director_spawns_the_wave_then_waits_out_the_intermission() {
+    let script = script();
+    let mut director = WaveDirector::default();
+
+    for _ in 0..3 {
+        assert_eq!(director.spawns_this_turn(&script, 1), 1);
+        director.spawned += 1;
+    }
+    assert!(director.is_wave_out());
+    assert_eq!(director.spawns_this_turn(&script, 4), 0);
+
+    assert!(director.finish_wave(&script));
+    assert_eq!(director.spawns_this_turn(&script, 5), 0);
+    assert_eq!(director.spawns_this_turn(&script, 6), 0);
+    assert_eq!(director.spawns_this_turn(&script, 7), 2);
+
+    // The last wave is over.
+    assert!(!director.finish_wave(&script));
+}
+
+#[test]
+fn later_turns_make_waves_bigger() {
+    let script = script();
+
+    assert_eq!(script.wave_size(1, 9), 4);
+    assert_eq!(script.wave_size(1, 25), 6);
+    assert_eq!(script.wave_size(2, 25), 0);
+}
+
+#[test]
+fn first_wave_spawns_one_enemy_per_turn() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+
+    let director = director(&harness);
+    assert_eq!(director.wave, 0);
+    assert_eq!(director.spawned, 1);
+    assert!(!director.is_wave_out());
+}
+
+#[test]
+fn enemies_spawned_in_one_turn_never_share_a_tile() {
+    let mut harness = Harness::new();
+    {
+        let world = harness.app.world_mut();
+        let handle = world.resource::<WaveScriptHandle>().0.clone();
+        let mut scripts = world.resource_mut::<Assets<WaveScript>>();
+        let wave = &mut scripts.get_mut(&handle).unwrap().waves[0];
+        wave.count = 8;
+        wave.per_turn = 8;
+    }
+    harness.start_gameplay();
+
+    let mut positions = harness.enemy_positions();
+    assert_eq!(positions.len(), 8);
+    positions.dedup();
+    assert_eq!(positions.len(), 8);
+}
+
+#[test]
+fn enemies_without_a_free_tile_are_not_spawned() {
+    let mut game = Game::new(Board::default(), RoundColor::Red);
+    game.add_unit(Side::Player, Pos::new(3, 14));
+    let tiles = [Pos::new(3, 14), Pos::new(4, 14)];
+
+    let events = game.spawn_enemies(&[EnemyKind::Grunt, EnemyKind::Tank], &tiles);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(
+        events[0],
+        Event::EnemySpawned {
+            pos,
+            kind: EnemyKind::Grunt,
+            ..
+        } if pos == Pos::new(4, 14)
+    ));
+    assert_eq!(game.enemies().count(), 1);
+}
+
+#[test]
+fn clearing_a_wave_starts_the_intermission() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    harness.app.world_mut().resource_mut::<WaveDirector>().size = 1;
+    harness.clear_enemies();
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+
+    harness.press(KeyCode::KeyQ);
+
+    let director = director(&harness);
+    assert_eq!(director.wave, 1);
+    assert!(director.intermission > 0);
+    assert_eq!(harness.turn_state(), Some(TurnState::MoveEnemy));
+
+    // No enemies come in during the intermission.
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.advance(ENEMY_ATTACK_WINDUP_SECONDS);
+    harness.advance(ENEMY_ATTACK_COOLDOWN_SECONDS);
+    assert!(harness.run_until(TurnState::MovePlayer, 10));
+    assert!(harness.enemy_positions().is_empty());
+}
+
+#[test]
+fn clearing_the_last_wave_wins_the_run() {
+    let mut harness = Harness::new();
+    harness.start_gameplay();
+    let waves = {
+        let world = harness.app.world();
+        let handle = &world.resource::<WaveScriptHandle>().0;
+        world.resource::<Assets<WaveScript>>().get(handle).unwrap().waves.len()
+    };
+    {
+        let mut director = harness.app.world_mut().resource_mut::<WaveDirector>();
+        director.wave = waves - 1;
+        director.size = 1;
+    }
+    harness.advance(MOVE_DELAY_SECONDS);
+    harness.press(KeyCode::ArrowUp);
+    harness.press(KeyCode::Space);
+    // The spawned enemy dies to the attack.
+    harness.clear_enemies();
+    harness.spawn_enemy(TilePos { x: 10, y: 4 });
+
+    harness.press(KeyCode::KeyW);
+    // The wave is checked when the enemy move starts, the end screen follows a frame later.
+    harness.update();
+
+    assert_eq!(harness.app_state(), ApplicationState::EndMenu);
+    assert_eq!(
+        *harness.app.world().resource::<RunOutcome>(),
+        RunOutcome::Victory
+    );
+}